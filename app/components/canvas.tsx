@@ -1,20 +1,52 @@
 import * as React from "react";
-import { forwardRef } from "react";
+import { forwardRef, useRef } from "react";
 
 interface Props {
   height: number;
   width: number;
+  onDrag?: (dx: number, dy: number, pan: boolean) => void;
+  onWheel?: (delta: number) => void;
 }
 
 type Ref = HTMLCanvasElement;
 
-export default forwardRef<Ref, Props>(({ height, width }, ref) => {
+export default forwardRef<Ref, Props>(({ height, width, onDrag, onWheel }, ref) => {
+  const last = useRef(null);
+
+  const pointerDown = (e: React.PointerEvent<HTMLCanvasElement>) => {
+    e.currentTarget.setPointerCapture(e.pointerId);
+    last.current = { x: e.clientX, y: e.clientY };
+  };
+
+  const pointerMove = (e: React.PointerEvent<HTMLCanvasElement>) => {
+    if (!last.current) {
+      return;
+    }
+    const dx = e.clientX - last.current.x;
+    const dy = e.clientY - last.current.y;
+    last.current = { x: e.clientX, y: e.clientY };
+    // Right button or shift-drag pans; anything else rotates.
+    onDrag && onDrag(dx, dy, e.shiftKey || (e.buttons & 2) !== 0);
+  };
+
+  const pointerUp = (e: React.PointerEvent<HTMLCanvasElement>) => {
+    e.currentTarget.releasePointerCapture(e.pointerId);
+    last.current = null;
+  };
+
   return (
     <div>
       <canvas
         id="parametric-surface" // id necessary for Rust-land
         ref={ref}
         height={height} width={width}
+        onPointerDown={pointerDown}
+        onPointerMove={pointerMove}
+        onPointerUp={pointerUp}
+        onPointerCancel={pointerUp}
+        onContextMenu={(e) => e.preventDefault()}
+        onWheel={(e) => onWheel && onWheel(e.deltaY)}
+        style={{ touchAction: "none" }}
       ></canvas>
     </div>
   )
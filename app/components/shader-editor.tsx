@@ -0,0 +1,48 @@
+import * as React from "react";
+import { useState } from "react";
+import "./shader-editor.css";
+
+interface Props {
+  surface: Record<string, any>;
+}
+
+// Compile errors carry one diagnostic per driver message, with an excerpt of the offending source.
+const describe = (e: any): string =>
+  e.diagnostics && e.diagnostics.length > 0
+    ? e.diagnostics.map((d: any) => `${d.file}:${d.line}: ${d.message}\n${d.excerpt}`).join("\n\n")
+    : e.message;
+
+export default ({ surface }: Props) => {
+  const [[vertex, fragment], setSources] = useState<string[]>(surface.default_shader_sources());
+  const [error, setError] = useState<string | null>(null);
+
+  const apply = () => {
+    try {
+      surface.set_shader_sources(vertex, fragment);
+      setError(null);
+    } catch (e) {
+      // The surface keeps drawing with its previous shaders.
+      setError(describe(e));
+    }
+  };
+
+  const reset = () => {
+    surface.reset_shader();
+    setSources(surface.default_shader_sources());
+    setError(null);
+  };
+
+  return (
+    <div className="shader-editor">
+      <span>Vertex shader</span>
+      <textarea value={vertex} spellCheck={false} onChange={(e) => setSources([e.target.value, fragment])} />
+      <span>Fragment shader</span>
+      <textarea value={fragment} spellCheck={false} onChange={(e) => setSources([vertex, e.target.value])} />
+      <div>
+        <button onClick={apply}>Apply shaders</button>
+        <button onClick={reset}>Reset shaders</button>
+      </div>
+      { error && <pre className="shader-editor-error">{error}</pre> }
+    </div>
+  );
+}
@@ -0,0 +1,32 @@
+import * as React from "react";
+import { useState } from "react";
+import "./slider.css";
+
+interface Props {
+  label: string;
+  min: number;
+  max: number;
+  step: number;
+  initial: number;
+  callback: (value: number) => void;
+}
+
+export default ({ label, min, max, step, initial, callback }: Props) => {
+  const [value, setValue] = useState(initial);
+
+  return (
+    <label className="slider">
+      <span>{`${label}: ${value}`}</span>
+      <input
+        type="range"
+        min={min} max={max} step={step}
+        value={value}
+        onChange={(e) => {
+          const next = Number(e.target.value);
+          setValue(next);
+          callback(next);
+        }}
+      />
+    </label>
+  );
+}
@@ -2,6 +2,8 @@ import * as React from "react";
 import { useEffect, useReducer, useRef } from "react";
 import Canvas from "@components/canvas";
 import Selector from "@components/selector";
+import ShaderEditor from "@components/shader-editor";
+import Slider from "@components/slider";
 import { ActionType, reducer, InitialState } from "./reducer"
 import "./index.css"
 
@@ -12,8 +14,23 @@ interface Props {
 const CANVAS_WIDTH = 800;
 const CANVAS_HEIGHT = 600;
 const FPS_THROTTLE = 1000.0 / 60;
+const DEFAULT_SUBDIVISIONS = 72;
+const DEFAULT_FOV = 45;
+const SCREENSHOT_SIZE = { width: 1920, height: 1080 };
+const TURNTABLE_SIZE = { width: 640, height: 480 };
+const TURNTABLE_FRAMES = 60;
+const TURNTABLE_FPS = 30;
 
-const SHAPES = ["Torus", "Cube"];
+const MESHES = ["Cube", "Triforce"];
+
+const download = (data: BlobPart, filename: string) => {
+  const url = URL.createObjectURL(new Blob([data]));
+  const link = document.createElement("a");
+  link.href = url;
+  link.download = filename;
+  link.click();
+  URL.revokeObjectURL(url);
+};
 
 export default ({ wasmModule }: Props) => {
   const [state, dispatch] = useReducer(reducer, InitialState);
@@ -21,6 +38,131 @@ export default ({ wasmModule }: Props) => {
   const initAnimation = useRef(true);
   const canvasRef = useRef(null);
   const initTime = Date.now();
+  const shapes = [...wasmModule.surface_names(), ...MESHES];
+  const subdivisions = useRef({ u: DEFAULT_SUBDIVISIONS, v: DEFAULT_SUBDIVISIONS });
+
+  const torusControls = (torus: Record<string, any>) => (
+    <div style={{ marginTop: "75%" }}>
+      <Slider
+        label="Major radius" min={0.1} max={0.8} step={0.01}
+        initial={torus.major_radius()}
+        callback={(r) => torus.set_major_radius(r)}
+      />
+      <Slider
+        label="Minor radius" min={0.05} max={0.5} step={0.01}
+        initial={torus.minor_radius()}
+        callback={(r) => torus.set_minor_radius(r)}
+      />
+      <Slider
+        label="U subdivisions" min={3} max={256} step={1}
+        initial={subdivisions.current.u}
+        callback={(u) => {
+          subdivisions.current.u = u;
+          torus.set_subdivisions(u, subdivisions.current.v);
+        }}
+      />
+      <Slider
+        label="V subdivisions" min={3} max={256} step={1}
+        initial={subdivisions.current.v}
+        callback={(v) => {
+          subdivisions.current.v = v;
+          torus.set_subdivisions(subdivisions.current.u, v);
+        }}
+      />
+    </div>
+  );
+
+  const dragCamera = (dx: number, dy: number, pan: boolean) => {
+    const surface = state.parametricSurface;
+    if (surface && typeof surface.orbit_camera === "function") {
+      pan ? surface.pan_camera(dx, dy) : surface.orbit_camera(dx, dy);
+    }
+  };
+
+  const zoomCamera = (delta: number) => {
+    const surface = state.parametricSurface;
+    if (surface && typeof surface.zoom_camera === "function") {
+      surface.zoom_camera(delta);
+    }
+  };
+
+  const cameraControls = (surface: Record<string, any>) => (
+    <div>
+      <Selector
+        options={["Perspective", "Orthographic"]}
+        callback={(p) => surface.set_projection(p)}
+      />
+      <Slider
+        label="Field of view" min={10} max={120} step={1}
+        initial={DEFAULT_FOV}
+        callback={(fov) => surface.set_fov(fov)}
+      />
+      <button onClick={() => surface.fit_camera(canvasRef.current.width, canvasRef.current.height)}>
+        Fit to view
+      </button>
+      <button onClick={() => surface.reset_camera()}>Reset camera</button>
+    </div>
+  );
+
+  const lightingControls = (surface: Record<string, any>) => (
+    <div>
+      <Slider
+        label="Shininess" min={1} max={256} step={1}
+        initial={32}
+        callback={(shininess) => surface.set_material_shininess(shininess)}
+      />
+      <button onClick={() => {
+        const light = surface.add_light("spot");
+        surface.set_light_position(light, 0, 2, 2);
+        surface.set_light_direction(light, 0, -1, -1);
+        surface.set_light_color(light, 1, 0.8, 0.6);
+      }}>Add spot light</button>
+      <button onClick={() => surface.light_count() > 0 && surface.remove_light(surface.light_count() - 1)}>
+        Remove light
+      </button>
+    </div>
+  );
+
+  const exportControls = (surface: Record<string, any>) => (
+    <div>
+      <button onClick={() => download(surface.export_obj("surface"), "surface.obj")}>Export OBJ</button>
+      <button onClick={() => {
+        try {
+          download(surface.export_stl("surface", true, 0), "surface.stl");
+        } catch (e) {
+          if (e.kind !== "topology") {
+            throw e;
+          }
+          // Open surfaces need thickening to print; fall back to a thin shell.
+          console.warn(`${e.message}; exporting a 0.02 thick shell instead.`);
+          download(surface.export_stl("surface", true, 0.02), "surface.stl");
+        }
+      }}>Export STL</button>
+      <button onClick={() => download(surface.export_glb("surface", true), "surface.glb")}>Export glTF</button>
+      <button onClick={() => download(
+        surface.export_ply(true, ["uv", "color", "gaussian_curvature", "mean_curvature"]), "surface.ply"
+      )}>Export PLY</button>
+    </div>
+  );
+
+  const captureControls = (surface: Record<string, any>) => (
+    <div>
+      <button onClick={() => {
+        const elapsedTime = (Date.now() - initTime) / 1000;
+        download(surface.screenshot(SCREENSHOT_SIZE.width, SCREENSHOT_SIZE.height, elapsedTime), "surface.png");
+      }}>Screenshot</button>
+      <button onClick={() => {
+        const frames: Uint8Array[] = surface.turntable_png(
+          TURNTABLE_SIZE.width, TURNTABLE_SIZE.height, TURNTABLE_FRAMES
+        );
+        frames.forEach((png, i) => download(png, `surface_${String(i).padStart(3, "0")}.png`));
+      }}>Turntable frames</button>
+      <button onClick={() => download(
+        surface.turntable_apng(TURNTABLE_SIZE.width, TURNTABLE_SIZE.height, TURNTABLE_FRAMES, TURNTABLE_FPS),
+        "surface_turntable.png"
+      )}>Turntable APNG</button>
+    </div>
+  );
 
   const animate = () => {
     const animationID = setInterval(() => {
@@ -46,13 +188,15 @@ export default ({ wasmModule }: Props) => {
         dispatch({ ...actionType, payload: new wasmModule.Cube("parametric-surface") });
         return;
       case "Torus":
+        subdivisions.current = { u: DEFAULT_SUBDIVISIONS, v: DEFAULT_SUBDIVISIONS };
         dispatch({ ...actionType, payload: new wasmModule.Torus("parametric-surface") });
         return;
       case "Triforce":
         dispatch({ ...actionType, payload: new wasmModule.Triforce("parametric-surface") });
         return;
       default:
-        return
+        dispatch({ ...actionType, payload: new wasmModule.CatalogSurface("parametric-surface", surface) });
+        return;
     }
   };
 
@@ -75,7 +219,7 @@ export default ({ wasmModule }: Props) => {
       const torus = new wasmModule.Torus("parametric-surface");
       dispatch({ kind: ActionType.SetSurface, payload: torus });
     } catch(e) {
-      dispatch({ kind: ActionType.Err, payload: `Failed to initialize parametric surface with error: ${e}` });
+      dispatch({ kind: ActionType.Err, payload: `Failed to initialize parametric surface with ${e.kind} error: ${e.message}` });
       return;
     }
   });
@@ -97,16 +241,31 @@ export default ({ wasmModule }: Props) => {
       { state.error ? <h1 style={{ color: "white" }}>{"Something went wrong."}</h1> :
 
       <>
-        <Selector
-          options={SHAPES}
-          callback={(s) => swapSurface(s)}
-          style={{ marginTop: "75%" }}
-        />
+        <div>
+          <Selector
+            options={shapes}
+            callback={(s) => swapSurface(s)}
+            style={{ marginTop: "75%" }}
+          />
+          { state.parametricSurface && typeof state.parametricSurface.set_projection === "function" &&
+            cameraControls(state.parametricSurface) }
+          { state.parametricSurface && typeof state.parametricSurface.set_material_shininess === "function" &&
+            lightingControls(state.parametricSurface) }
+          { state.parametricSurface && typeof state.parametricSurface.export_obj === "function" &&
+            exportControls(state.parametricSurface) }
+          { state.parametricSurface && typeof state.parametricSurface.screenshot === "function" &&
+            captureControls(state.parametricSurface) }
+        </div>
         <Canvas
           width={CANVAS_WIDTH}
           height={CANVAS_HEIGHT}
           ref={canvasRef}
+          onDrag={dragCamera}
+          onWheel={zoomCamera}
         />
+        { state.parametricSurface instanceof wasmModule.Torus && torusControls(state.parametricSurface) }
+        { state.parametricSurface && typeof state.parametricSurface.set_shader_sources === "function" &&
+          <ShaderEditor key={state.parametricSurface.constructor.name} surface={state.parametricSurface} /> }
       </>
 
       }
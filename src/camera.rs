@@ -0,0 +1,311 @@
+use crate::parametric_surfaces::mesh::Bounds;
+use nalgebra_glm as glm;
+use std::f32::consts::PI;
+
+/// Radians of rotation per pixel of pointer movement.
+const ROTATE_SPEED: f32 = 0.01;
+/// Fraction of the camera's distance panned per pixel of pointer movement.
+const PAN_SPEED: f32 = 0.0015;
+/// Exponential zoom rate per unit of wheel delta.
+const ZOOM_SPEED: f32 = 0.001;
+const MIN_DISTANCE: f32 = 0.1;
+const MAX_DISTANCE: f32 = 100.0;
+/// Closest the orbit camera may get to looking straight up or down, in radians.
+const POLE_MARGIN: f32 = PI / 180.0;
+/// Default vertical field of view, in radians.
+const FOV: f32 = PI / 4.0;
+const NEAR: f32 = 0.1;
+const FAR: f32 = 100.0;
+/// Extra room left around a bounding sphere when fitting the camera to it.
+const FIT_MARGIN: f32 = 1.05;
+
+/// How pointer drags rotate the camera.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RotationMode {
+    /// Turntable rotation: horizontal drags yaw around the world's y-axis and vertical drags
+    /// pitch, stopping short of the poles so the horizon never flips.
+    Orbit,
+    /// Free trackball rotation about the axis perpendicular to the drag, in view space.
+    Arcball,
+}
+
+impl RotationMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "orbit" | "turntable" => Some(Self::Orbit),
+            "arcball" | "trackball" => Some(Self::Arcball),
+            _ => None,
+        }
+    }
+}
+
+/// How the view volume is projected onto the canvas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Projection {
+    Perspective,
+    /// Parallel projection. The visible height is what the perspective frustum would show at the
+    /// target, so switching modes keeps the target's apparent size and dollying still zooms.
+    Orthographic,
+}
+
+impl Projection {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "perspective" => Some(Self::Perspective),
+            "orthographic" | "ortho" => Some(Self::Orthographic),
+            _ => None,
+        }
+    }
+}
+
+/// Camera circling `target` at `distance`. `orientation` rotates view space into world space, so
+/// the camera sits at `target + orientation * (0, 0, distance)` looking back at `target`.
+#[derive(Clone, Debug)]
+pub struct Camera {
+    pub target: glm::Vec3,
+    pub distance: f32,
+    pub orientation: glm::Quat,
+    pub mode: RotationMode,
+    pub projection: Projection,
+    /// Vertical field of view, in radians.
+    pub fov: f32,
+    pub near: f32,
+    pub far: f32,
+    initial_distance: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new(2.5)
+    }
+}
+
+impl Camera {
+    /// Camera on the positive z-axis looking at the origin from `distance` away.
+    pub fn new(distance: f32) -> Self {
+        Self {
+            target: glm::vec3(0.0, 0.0, 0.0),
+            distance,
+            orientation: glm::quat_identity(),
+            mode: RotationMode::Orbit,
+            projection: Projection::Perspective,
+            fov: FOV,
+            near: NEAR,
+            far: FAR,
+            initial_distance: distance,
+        }
+    }
+
+    pub fn eye(&self) -> glm::Vec3 {
+        self.target + glm::quat_rotate_vec3(&self.orientation, &glm::vec3(0.0, 0.0, self.distance))
+    }
+
+    pub fn up(&self) -> glm::Vec3 {
+        glm::quat_rotate_vec3(&self.orientation, &glm::vec3(0.0, 1.0, 0.0))
+    }
+
+    pub fn right(&self) -> glm::Vec3 {
+        glm::quat_rotate_vec3(&self.orientation, &glm::vec3(1.0, 0.0, 0.0))
+    }
+
+    pub fn view_matrix(&self) -> glm::TMat4<f32> {
+        glm::look_at(&self.eye(), &self.target, &self.up())
+    }
+
+    pub fn projection_matrix(&self, aspect_ratio: f32) -> glm::TMat4<f32> {
+        match self.projection {
+            Projection::Perspective => glm::perspective(aspect_ratio, self.fov, self.near, self.far),
+            Projection::Orthographic => {
+                let half_height = self.distance * (self.fov / 2.0).tan();
+                let half_width = half_height * aspect_ratio;
+                glm::ortho(-half_width, half_width, -half_height, half_height, self.near, self.far)
+            }
+        }
+    }
+
+    /// Sets the clip planes, rejecting any pair that doesn't satisfy `0 < near < far < ∞`.
+    pub fn set_clip_planes(&mut self, near: f32, far: f32) -> Result<(), String> {
+        if !(near > 0.0 && near < far && far.is_finite()) {
+            return Err(format!("Invalid clip planes, near {} and far {}", near, far));
+        }
+        self.near = near;
+        self.far = far;
+        Ok(())
+    }
+
+    /// Centres the camera on `bounds` and backs it off until the box's bounding sphere fills the
+    /// narrower side of the view. In orthographic mode the extents follow from the new distance.
+    /// The far plane is pushed out, and the near plane pulled in, if they would clip the sphere.
+    pub fn fit(&mut self, bounds: &Bounds, aspect_ratio: f32) {
+        let radius = bounds.radius().max(MIN_DISTANCE) * FIT_MARGIN;
+        let half_fov = self.fov / 2.0;
+        let half_fov = half_fov.min(((half_fov).tan() * aspect_ratio).atan());
+
+        self.target = bounds.center();
+        self.distance = (radius / half_fov.sin()).clamp(MIN_DISTANCE, MAX_DISTANCE);
+        self.far = self.far.max(self.distance + radius);
+        if self.distance - radius < self.near {
+            self.near = ((self.distance - radius) / 2.0).max(self.distance / 1000.0);
+        }
+    }
+
+    /// Rotates the camera around its target for a pointer drag of (dx, dy) pixels. Dragging right
+    /// or down turns the scene the same way, as if grabbing it.
+    pub fn rotate(&mut self, dx: f32, dy: f32) {
+        match self.mode {
+            RotationMode::Orbit => self.orbit(dx, dy),
+            RotationMode::Arcball => self.arcball(dx, dy),
+        }
+    }
+
+    fn orbit(&mut self, dx: f32, dy: f32) {
+        let yaw = glm::quat_angle_axis(-dx * ROTATE_SPEED, &glm::vec3(0.0, 1.0, 0.0));
+        self.orientation = glm::quat_normalize(&(yaw * self.orientation));
+
+        let pitch = glm::quat_angle_axis(-dy * ROTATE_SPEED, &glm::vec3(1.0, 0.0, 0.0));
+        let pitched = glm::quat_normalize(&(self.orientation * pitch));
+        let back = glm::quat_rotate_vec3(&pitched, &glm::vec3(0.0, 0.0, 1.0));
+        let up = glm::quat_rotate_vec3(&pitched, &glm::vec3(0.0, 1.0, 0.0));
+
+        // Past a pole the camera's up vector points below the horizon, which would flip the view.
+        if up.y > 0.0 && back.y.abs() < (PI / 2.0 - POLE_MARGIN).sin() {
+            self.orientation = pitched;
+        }
+    }
+
+    fn arcball(&mut self, dx: f32, dy: f32) {
+        let drag = glm::vec2(dx, dy);
+        let length = glm::length(&drag);
+        if length == 0.0 {
+            return;
+        }
+
+        let axis = glm::vec3(dy, dx, 0.0) / length;
+        let rotation = glm::quat_angle_axis(-length * ROTATE_SPEED, &axis);
+        self.orientation = glm::quat_normalize(&(self.orientation * rotation));
+    }
+
+    /// Slides the camera and its target parallel to the view plane for a drag of (dx, dy) pixels.
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let scale = self.distance * PAN_SPEED;
+        self.target += (self.up() * dy - self.right() * dx) * scale;
+    }
+
+    /// Moves the camera towards (negative `delta`) or away from its target, e.g. by a wheel
+    /// event's `deltaY`.
+    pub fn dolly(&mut self, delta: f32) {
+        self.distance = (self.distance * (delta * ZOOM_SPEED).exp()).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+
+    /// Returns to the position the camera was created with, keeping its rotation mode.
+    pub fn reset(&mut self) {
+        self.target = glm::vec3(0.0, 0.0, 0.0);
+        self.distance = self.initial_distance;
+        self.orientation = glm::quat_identity();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn back(camera: &Camera) -> glm::Vec3 {
+        glm::normalize(&(camera.eye() - camera.target))
+    }
+
+    #[test]
+    fn orbiting_stops_short_of_the_poles() {
+        for direction in [1.0, -1.0] {
+            let mut camera = Camera::default();
+            for _ in 0..100 {
+                camera.rotate(3.0, direction * 10.0);
+                assert!(back(&camera).y.abs() < (PI / 2.0 - POLE_MARGIN).sin());
+                assert!(camera.up().y > 0.0);
+            }
+            // Dragging further keeps it pinned near the pole rather than flipping over.
+            assert!(back(&camera).y.abs() > (PI / 2.0 - 0.1).sin());
+        }
+    }
+
+    #[test]
+    fn arcball_keeps_the_view_orthonormal() {
+        let mut camera = Camera { mode: RotationMode::Arcball, ..Camera::default() };
+        for (dx, dy) in [(40.0, 0.0), (0.0, -75.0), (120.0, 90.0), (-33.0, 250.0), (0.0, 0.0)] {
+            camera.rotate(dx, dy);
+            let (up, right, back) = (camera.up(), camera.right(), back(&camera));
+            assert!((glm::length(&up) - 1.0).abs() < 1e-5);
+            assert!(glm::dot(&up, &back).abs() < 1e-5);
+            assert!(glm::dot(&up, &right).abs() < 1e-5);
+        }
+        // Unlike orbiting, an arcball can roll the horizon.
+        assert!(camera.up().y < 0.99);
+    }
+
+    #[test]
+    fn dolly_stays_in_range() {
+        let mut camera = Camera::default();
+        camera.dolly(-1e6);
+        assert_eq!(camera.distance, MIN_DISTANCE);
+        assert!(glm::distance(&camera.eye(), &camera.target) > 0.0);
+        camera.dolly(1e6);
+        assert_eq!(camera.distance, MAX_DISTANCE);
+
+        let mut camera = Camera::new(2.0);
+        camera.dolly(-100.0);
+        assert!(camera.distance < 2.0);
+    }
+
+    #[test]
+    fn clip_planes_must_be_ordered_and_finite() {
+        let mut camera = Camera::default();
+        for (near, far) in [(0.0, 10.0), (-1.0, 10.0), (5.0, 5.0), (5.0, 1.0), (f32::NAN, 10.0), (0.1, f32::INFINITY)] {
+            assert!(camera.set_clip_planes(near, far).is_err(), "accepted near {} and far {}", near, far);
+            assert_eq!((camera.near, camera.far), (NEAR, FAR));
+        }
+        camera.set_clip_planes(0.5, 20.0).unwrap();
+        assert_eq!((camera.near, camera.far), (0.5, 20.0));
+    }
+
+    #[test]
+    fn fitting_keeps_the_whole_box_in_view() {
+        let bounds = Bounds { min: glm::vec3(-1.0, -0.5, 2.0), max: glm::vec3(3.0, 1.0, 4.0) };
+        let corners = (0..8).map(|i| glm::vec3(
+            if i & 1 == 0 { bounds.min.x } else { bounds.max.x },
+            if i & 2 == 0 { bounds.min.y } else { bounds.max.y },
+            if i & 4 == 0 { bounds.min.z } else { bounds.max.z },
+        ));
+
+        for aspect_ratio in [2.0, 0.5] {
+            for projection in [Projection::Perspective, Projection::Orthographic] {
+                let mut camera = Camera { projection, ..Camera::default() };
+                camera.rotate(40.0, 25.0);
+                camera.fit(&bounds, aspect_ratio);
+                assert_eq!(camera.target, bounds.center());
+
+                let clip = camera.projection_matrix(aspect_ratio) * camera.view_matrix();
+                for corner in corners.clone() {
+                    let p = clip * glm::vec4(corner.x, corner.y, corner.z, 1.0);
+                    let ndc = p.xyz() / p.w;
+                    assert!(p.w > 0.0);
+                    assert!(ndc.iter().all(|c| c.abs() <= 1.0), "{:?} at aspect {} is out of view: {:?}", projection, aspect_ratio, ndc);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reset_restores_the_initial_view() {
+        let mut camera = Camera::new(3.0);
+        camera.mode = RotationMode::Arcball;
+        camera.rotate(50.0, 20.0);
+        camera.pan(10.0, -10.0);
+        camera.dolly(300.0);
+
+        camera.reset();
+        assert_eq!(camera.target, glm::vec3(0.0, 0.0, 0.0));
+        assert_eq!(camera.distance, 3.0);
+        assert_eq!(camera.eye(), glm::vec3(0.0, 0.0, 3.0));
+        assert_eq!(camera.up(), glm::vec3(0.0, 1.0, 0.0));
+        assert_eq!(camera.mode, RotationMode::Arcball);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::export::gltf::GltfError;
+use crate::export::ply::PlyError;
+use crate::parametric_surfaces::expression::ExpressionError;
+use crate::parametric_surfaces::mesh::{Edge, TopologyError};
+use nalgebra_glm as glm;
+use std::fmt;
+use wasm_bindgen::JsValue;
+
+pub use crate::shader::Diagnostic;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+}
+
+impl fmt::Display for ShaderStage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShaderStage::Vertex => write!(f, "vertex"),
+            ShaderStage::Fragment => write!(f, "fragment"),
+        }
+    }
+}
+
+/// Everything that can go wrong setting up or drawing a scene. Crosses into JS as an `Error` whose
+/// `kind` names the variant, so callers can tell a lost context from a bad expression.
+#[derive(Clone, Debug)]
+pub enum Error {
+    /// No window, document or canvas to draw into, or the canvas can't give a WebGL context.
+    Context(String),
+    /// `diagnostics` holds the errors of `log` located in the source, where the format is known.
+    ShaderCompile { stage: ShaderStage, log: String, diagnostics: Vec<Diagnostic> },
+    ShaderLink { log: String },
+    /// A bad `#include` in GLSL source, before it reaches the driver.
+    Preprocess { file: String, line: usize, message: String },
+    /// The uniform isn't declared, or was optimised out of the linked program.
+    MissingUniform(String),
+    MissingAttribute(String),
+    /// A uniform set, or attribute bound, as a different GLSL type than the program declares.
+    TypeMismatch { name: String, declared: &'static str, used: &'static str },
+    /// A GL object (buffer, texture, framebuffer, ...) couldn't be created, usually because the
+    /// context was lost.
+    Allocation(&'static str),
+    TextureLoad { url: String, message: String },
+    /// The device lacks something the object needs, like 32-bit indices.
+    Unsupported(String),
+    InvalidArgument(String),
+    UnknownSurface(String),
+    Expression(ExpressionError),
+    Topology(TopologyError),
+    /// A mesh the named export `format` can't represent, or a bad set of fields for it.
+    Export { format: &'static str, message: String },
+    /// An exception thrown by a browser API.
+    Js(JsValue),
+}
+
+impl Error {
+    /// The `kind` property of the JS error.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Context(_) => "context",
+            Error::ShaderCompile { .. } => "shaderCompile",
+            Error::ShaderLink { .. } => "shaderLink",
+            Error::Preprocess { .. } => "preprocess",
+            Error::MissingUniform(_) => "missingUniform",
+            Error::MissingAttribute(_) => "missingAttribute",
+            Error::TypeMismatch { .. } => "typeMismatch",
+            Error::Allocation(_) => "allocation",
+            Error::TextureLoad { .. } => "textureLoad",
+            Error::Unsupported(_) => "unsupported",
+            Error::InvalidArgument(_) => "invalidArgument",
+            Error::UnknownSurface(_) => "unknownSurface",
+            Error::Expression(_) => "expression",
+            Error::Topology(_) => "topology",
+            Error::Export { .. } => "export",
+            Error::Js(_) => "js",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Context(message) => write!(f, "{}", message),
+            Error::ShaderCompile { stage, log, diagnostics } if diagnostics.is_empty() => {
+                write!(f, "An error occurred compiling {} shader: {}", stage, log)
+            }
+            Error::ShaderCompile { stage, diagnostics, .. } => {
+                write!(f, "An error occurred compiling {} shader:", stage)?;
+                diagnostics.iter().try_for_each(|diagnostic| write!(f, "\n{}", diagnostic))
+            }
+            Error::ShaderLink { log } => write!(f, "An error occurred linking shader program: {}", log),
+            Error::Preprocess { file, line, message } => write!(f, "{}:{}: {}", file, line, message),
+            Error::MissingUniform(name) => write!(f, "Failed to get location for uniform, '{}'", name),
+            Error::MissingAttribute(name) => write!(f, "Failed to get location for attribute, '{}'", name),
+            Error::TypeMismatch { name, declared, used } => {
+                write!(f, "'{}' is declared as {} but was used as {}", name, declared, used)
+            }
+            Error::Allocation(what) => write!(f, "Failed to initialize {}.", what),
+            Error::TextureLoad { url, message } => write!(f, "Failed to load texture '{}': {}", url, message),
+            Error::Unsupported(message) | Error::InvalidArgument(message) => write!(f, "{}", message),
+            Error::UnknownSurface(name) => write!(f, "Unknown parametric surface, '{}'", name),
+            Error::Expression(e @ ExpressionError::Parse { .. }) => write!(f, "Failed to parse {}", e),
+            Error::Expression(e) => write!(f, "Invalid expression surface: {}", e),
+            Error::Topology(e) => write!(f, "{}", e),
+            Error::Export { format, message } => write!(f, "Failed to export {}: {}", format, message),
+            Error::Js(value) => match value.as_string() {
+                Some(message) => write!(f, "{}", message),
+                None => write!(f, "{:?}", value),
+            },
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The value of a `Number` argument from JS, or [`Error::InvalidArgument`] naming the parameter if
+/// JS passed something else, which the `Number` type alone doesn't prevent.
+pub fn number(value: &js_sys::Number, name: &str) -> Result<f64> {
+    value.as_f64().ok_or_else(|| Error::InvalidArgument(format!("'{}' must be a number", name)))
+}
+
+/// Three [`number`] arguments, each with its parameter name, as a vector.
+pub fn vec3(components: [(&js_sys::Number, &str); 3]) -> Result<glm::Vec3> {
+    let [x, y, z] = components;
+    Ok(glm::vec3(number(x.0, x.1)? as f32, number(y.0, y.1)? as f32, number(z.0, z.1)? as f32))
+}
+
+impl From<JsValue> for Error {
+    fn from(value: JsValue) -> Self {
+        Error::Js(value)
+    }
+}
+
+impl From<ExpressionError> for Error {
+    fn from(e: ExpressionError) -> Self {
+        Error::Expression(e)
+    }
+}
+
+impl From<TopologyError> for Error {
+    fn from(e: TopologyError) -> Self {
+        Error::Topology(e)
+    }
+}
+
+impl From<GltfError> for Error {
+    fn from(e: GltfError) -> Self {
+        Error::Export { format: "glTF", message: e.to_string() }
+    }
+}
+
+impl From<PlyError> for Error {
+    fn from(e: PlyError) -> Self {
+        Error::Export { format: "PLY", message: e.to_string() }
+    }
+}
+
+impl From<Error> for JsValue {
+    fn from(e: Error) -> Self {
+        use js_sys::{Array, Reflect};
+
+        let error = js_sys::Error::new(&e.to_string());
+        // Setting a property on a fresh `Error` object can't fail.
+        let set = |key: &str, value: JsValue| {
+            Reflect::set(&error, &JsValue::from_str(key), &value).unwrap();
+        };
+        set("kind", JsValue::from_str(e.kind()));
+
+        match e {
+            Error::ShaderCompile { stage, log, diagnostics } => {
+                set("stage", JsValue::from(stage.to_string()));
+                set("log", JsValue::from(log));
+                let diagnostics = diagnostics.into_iter().map(|diagnostic| {
+                    let object = js_sys::Object::new();
+                    let field = |key: &str, value: JsValue| {
+                        Reflect::set(&object, &JsValue::from_str(key), &value).unwrap();
+                    };
+                    field("file", JsValue::from(diagnostic.file));
+                    field("line", JsValue::from(diagnostic.line as u32));
+                    field("column", diagnostic.column.map_or(JsValue::NULL, |column| JsValue::from(column as u32)));
+                    field("message", JsValue::from(diagnostic.message));
+                    field("excerpt", JsValue::from(diagnostic.excerpt));
+                    object
+                });
+                set("diagnostics", diagnostics.collect::<Array>().into());
+            }
+            Error::ShaderLink { log } => set("log", JsValue::from(log)),
+            Error::Preprocess { file, line, .. } => {
+                set("file", JsValue::from(file));
+                set("line", JsValue::from(line as u32));
+            }
+            Error::MissingUniform(name) | Error::MissingAttribute(name) => set("name", JsValue::from(name)),
+            Error::TypeMismatch { name, declared, used } => {
+                set("name", JsValue::from(name));
+                set("declared", JsValue::from_str(declared));
+                set("used", JsValue::from_str(used));
+            }
+            Error::TextureLoad { url, .. } => set("url", JsValue::from(url)),
+            Error::Export { format, .. } => set("format", JsValue::from_str(format)),
+            Error::Topology(e) => {
+                // `[a, b]` vertex index pairs, so a UI can highlight the offending edges.
+                let edges = |edges: &[Edge]| edges.iter()
+                    .map(|edge| Array::of2(&JsValue::from(edge.a), &JsValue::from(edge.b)))
+                    .collect::<Array>();
+                set("boundaryEdges", edges(&e.boundary_edges).into());
+                set("nonManifoldEdges", edges(&e.non_manifold_edges).into());
+            }
+            Error::Js(value) => set("cause", value),
+            _ => {}
+        }
+
+        error.into()
+    }
+}
@@ -0,0 +1,351 @@
+use super::seams::UnfoldedSeams;
+use crate::parametric_surfaces::mesh::{Indices, Mesh};
+use crate::parametric_surfaces::Spin;
+use nalgebra_glm as glm;
+use std::f32::consts::PI;
+use std::fmt;
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_VERSION: u32 = 2;
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+
+const FLOAT: u32 = 5126;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Keyframes per revolution of the spin animation. Slerping between them traces the same rotation
+/// as the renderer, so this only needs to keep consecutive keys less than half a turn apart.
+const SPIN_KEYFRAMES: usize = 32;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GltfError {
+    /// A vertex attribute is NaN or infinite, e.g. from an expression dividing by zero. JSON has no
+    /// way to write the bounds of such a mesh.
+    NonFinite { attribute: &'static str, vertex: usize },
+}
+
+impl fmt::Display for GltfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonFinite { attribute, vertex } => {
+                write!(f, "vertex {} has a non-finite {}, which glTF can't store", vertex, attribute)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GltfError {}
+
+/// Writes `mesh` as a binary glTF 2.0 (.glb) scene with a single node called `name`.
+///
+/// Vertex colours are multiplied by a white, fully rough, non-metallic material, which is the
+/// closest PBR match to the renderer's diffuse lighting. Vertices across the seams of periodic
+/// surfaces are duplicated with texture coordinates shifted by one, so textures don't smear back
+/// across the closing triangles. With `spin` set, the node is given the renderer's rotation at
+/// `dtheta = 0` and a looping animation through one full revolution, one second of animation per
+/// unit of `dtheta`.
+pub fn to_glb(mesh: &Mesh, name: &str, spin: Option<Spin>) -> Result<Vec<u8>, GltfError> {
+    let attributes = [("position", &mesh.positions), ("normal", &mesh.normals), ("colour", &mesh.colors)];
+    for (attribute, values) in attributes {
+        if let Some(i) = values.iter().position(|x| !x.is_finite()) {
+            return Err(GltfError::NonFinite { attribute, vertex: i / 3 });
+        }
+    }
+
+    let mut bin = BinaryBuffer::default();
+    let mut accessors = vec![];
+
+    // glTF has one index per vertex, so each shifted texture coordinate needs a copy of its vertex.
+    let seams = UnfoldedSeams::new(mesh);
+    let copied = seams.copied_vertices(mesh.vertex_count());
+    let vertex_count = seams.tex_coords.len();
+    let with_copies = |values: &[f32]| -> Vec<f32> {
+        let copies = copied.iter().flat_map(|&i| values[i * 3..i * 3 + 3].to_vec());
+        values.iter().copied().chain(copies).collect()
+    };
+
+    // *======== Vertex attributes ========*
+    let (min, max) = match mesh.bounds() {
+        Some(bounds) => (bounds.min, bounds.max),
+        None => (glm::Vec3::zeros(), glm::Vec3::zeros()),
+    };
+    let view = bin.push_f32(&with_copies(&mesh.positions), Some(ARRAY_BUFFER));
+    accessors.push(format!(
+        r#"{{"bufferView":{},"componentType":{},"count":{},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}}"#,
+        view, FLOAT, vertex_count, min.x, min.y, min.z, max.x, max.y, max.z
+    ));
+
+    let view = bin.push_f32(&with_copies(&mesh.normals), Some(ARRAY_BUFFER));
+    accessors.push(accessor(view, FLOAT, vertex_count, "VEC3"));
+
+    let view = bin.push_f32(seams.tex_coords.as_flattened(), Some(ARRAY_BUFFER));
+    accessors.push(accessor(view, FLOAT, vertex_count, "VEC2"));
+
+    // Renderer colours can overshoot 1; glTF colours are factors in [0, 1].
+    let colors: Vec<f32> = with_copies(&mesh.colors).iter().map(|c| c.clamp(0.0, 1.0)).collect();
+    let view = bin.push_f32(&colors, Some(ARRAY_BUFFER));
+    accessors.push(accessor(view, FLOAT, vertex_count, "VEC3"));
+
+    // *======== Indices ========*
+    // Texture coordinate indices double as vertex indices once the copies are appended.
+    let indices = Indices::new(seams.corners.iter().map(|&(_, t)| t as u32).collect(), vertex_count);
+    let (view, component_type) = match &indices {
+        Indices::U16(indices) => {
+            let bytes: Vec<u8> = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+            (bin.push(&bytes, Some(ELEMENT_ARRAY_BUFFER)), UNSIGNED_SHORT)
+        }
+        Indices::U32(indices) => {
+            let bytes: Vec<u8> = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+            (bin.push(&bytes, Some(ELEMENT_ARRAY_BUFFER)), UNSIGNED_INT)
+        }
+    };
+    accessors.push(accessor(view, component_type, indices.len(), "SCALAR"));
+
+    // *======== Spin animation ========*
+    let mut node = format!(r#"{{"name":{},"mesh":0"#, json_string(name));
+    let mut animations = String::new();
+
+    if let Some(spin) = spin {
+        let rotation = spin.rotation(0.0).coords;
+        node += &format!(r#","rotation":[{},{},{},{}]"#, rotation.x, rotation.y, rotation.z, rotation.w);
+
+        let period = 2.0 * PI;
+        let times: Vec<f32> = (0..=SPIN_KEYFRAMES).map(|k| period * k as f32 / SPIN_KEYFRAMES as f32).collect();
+        let rotations: Vec<f32> = times.iter().flat_map(|&t| {
+            let q = spin.rotation(t).coords;
+            [q.x, q.y, q.z, q.w]
+        }).collect();
+
+        let input = accessors.len();
+        let view = bin.push_f32(&times, None);
+        accessors.push(format!(
+            r#"{{"bufferView":{},"componentType":{},"count":{},"type":"SCALAR","min":[0],"max":[{}]}}"#,
+            view, FLOAT, times.len(), period
+        ));
+
+        let output = accessors.len();
+        let view = bin.push_f32(&rotations, None);
+        accessors.push(accessor(view, FLOAT, times.len(), "VEC4"));
+
+        animations = format!(
+            r#","animations":[{{"name":"spin","samplers":[{{"input":{},"output":{},"interpolation":"LINEAR"}}],"channels":[{{"sampler":0,"target":{{"node":0,"path":"rotation"}}}}]}}]"#,
+            input, output
+        );
+    }
+    node.push('}');
+
+    let json = format!(
+        concat!(
+            r#"{{"asset":{{"version":"2.0","generator":"parametric_surfaces"}},"#,
+            r#""scene":0,"scenes":[{{"nodes":[0]}}],"nodes":[{}],"#,
+            r#""meshes":[{{"name":{},"primitives":[{{"attributes":{{"POSITION":0,"NORMAL":1,"TEXCOORD_0":2,"COLOR_0":3}},"indices":4,"material":0}}]}}],"#,
+            r#""materials":[{{"name":"surface","doubleSided":true,"pbrMetallicRoughness":{{"baseColorFactor":[1,1,1,1],"metallicFactor":0,"roughnessFactor":1}}}}],"#,
+            r#""buffers":[{{"byteLength":{}}}],"bufferViews":[{}],"accessors":[{}]{}}}"#
+        ),
+        node, json_string(name), bin.data.len(), bin.views.join(","), accessors.join(","), animations
+    );
+
+    Ok(glb(json.into_bytes(), bin.data))
+}
+
+/// The binary chunk, and the views into it, built up one tightly packed array at a time.
+#[derive(Default)]
+struct BinaryBuffer {
+    data: Vec<u8>,
+    views: Vec<String>,
+}
+
+impl BinaryBuffer {
+    /// Appends `bytes` as a new buffer view and returns its index.
+    fn push(&mut self, bytes: &[u8], target: Option<u32>) -> usize {
+        // Accessor offsets must be a multiple of the component size; 4 covers every type here.
+        pad(&mut self.data, 0);
+        let target = target.map(|t| format!(r#","target":{}"#, t)).unwrap_or_default();
+        self.views.push(format!(
+            r#"{{"buffer":0,"byteOffset":{},"byteLength":{}{}}}"#, self.data.len(), bytes.len(), target
+        ));
+        self.data.extend_from_slice(bytes);
+        self.views.len() - 1
+    }
+
+    fn push_f32(&mut self, values: &[f32], target: Option<u32>) -> usize {
+        let bytes: Vec<u8> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+        self.push(&bytes, target)
+    }
+}
+
+fn accessor(view: usize, component_type: u32, count: usize, kind: &str) -> String {
+    format!(
+        r#"{{"bufferView":{},"componentType":{},"count":{},"type":"{}"}}"#, view, component_type, count, kind
+    )
+}
+
+/// Pads to a 4-byte boundary, as required between GLB chunks and buffer views.
+fn pad(bytes: &mut Vec<u8>, with: u8) {
+    while bytes.len() % 4 != 0 {
+        bytes.push(with);
+    }
+}
+
+fn glb(mut json: Vec<u8>, mut bin: Vec<u8>) -> Vec<u8> {
+    pad(&mut json, b' ');
+    pad(&mut bin, 0);
+
+    let length = 12 + 8 + json.len() + 8 + bin.len();
+    let mut glb = Vec::with_capacity(length);
+    for word in [GLB_MAGIC, GLB_VERSION, length as u32, json.len() as u32, CHUNK_JSON] {
+        glb.extend_from_slice(&word.to_le_bytes());
+    }
+    glb.extend_from_slice(&json);
+    for word in [bin.len() as u32, CHUNK_BIN] {
+        glb.extend_from_slice(&word.to_le_bytes());
+    }
+    glb.extend_from_slice(&bin);
+
+    glb
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parametric_surfaces::catalog;
+    use crate::parametric_surfaces::mesh::tessellate;
+    use crate::parametric_surfaces::torus::TorusGeometry;
+    use serde_json::Value;
+
+    /// The parsed JSON chunk and the binary chunk, after checking the GLB header and chunk layout.
+    fn chunks(glb: &[u8]) -> (Value, &[u8]) {
+        let word = |offset: usize| u32::from_le_bytes(glb[offset..offset + 4].try_into().unwrap()) as usize;
+        assert_eq!([word(0), word(4), word(8)], [GLB_MAGIC as usize, GLB_VERSION as usize, glb.len()]);
+
+        let json_length = word(12);
+        assert_eq!(word(16), CHUNK_JSON as usize);
+        let json = serde_json::from_slice(&glb[20..20 + json_length]).unwrap();
+
+        let bin = 20 + json_length;
+        assert_eq!(word(bin + 4), CHUNK_BIN as usize);
+        assert_eq!(bin + 8 + word(bin), glb.len());
+        (json, &glb[bin + 8..])
+    }
+
+    /// Little-endian `f32`s of accessor `index`.
+    fn floats(json: &Value, bin: &[u8], index: usize) -> Vec<f32> {
+        let view = &json["bufferViews"][json["accessors"][index]["bufferView"].as_u64().unwrap() as usize];
+        let offset = view["byteOffset"].as_u64().unwrap() as usize;
+        let length = view["byteLength"].as_u64().unwrap() as usize;
+        bin[offset..offset + length]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect()
+    }
+
+    fn indices(json: &Value, bin: &[u8]) -> Vec<usize> {
+        let accessor = &json["accessors"][4];
+        let view = &json["bufferViews"][accessor["bufferView"].as_u64().unwrap() as usize];
+        let offset = view["byteOffset"].as_u64().unwrap() as usize;
+        let length = view["byteLength"].as_u64().unwrap() as usize;
+        assert_eq!(accessor["componentType"], UNSIGNED_SHORT);
+        bin[offset..offset + length]
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+            .collect()
+    }
+
+    #[test]
+    fn writes_a_valid_scene() {
+        let mesh = tessellate(&TorusGeometry::default(), 8, 6);
+        let glb = to_glb(&mesh, "a \"torus\"", None).unwrap();
+        let (json, bin) = chunks(&glb);
+
+        assert_eq!(json["asset"]["version"], "2.0");
+        assert_eq!(json["nodes"][0]["name"], "a \"torus\"");
+        assert!(json.get("animations").is_none());
+        assert_eq!(json["buffers"][0]["byteLength"].as_u64().unwrap() as usize, bin.len());
+
+        let bounds = mesh.bounds().unwrap();
+        let positions = &json["accessors"][0];
+        assert_eq!(positions["min"][0].as_f64().unwrap() as f32, bounds.min.x);
+        assert_eq!(positions["max"][2].as_f64().unwrap() as f32, bounds.max.z);
+    }
+
+    #[test]
+    fn seam_vertices_are_copied_with_shifted_texture_coordinates() {
+        let (nu, nv) = (4, 3);
+        let mesh = tessellate(&TorusGeometry::default(), nu, nv);
+        let glb = to_glb(&mesh, "torus", None).unwrap();
+        let (json, bin) = chunks(&glb);
+
+        // A copy of column 0 at u = 1, of row 0 at v = 1, and the corner at (1, 1).
+        let vertex_count = mesh.vertex_count() + nv + nu + 1;
+        for accessor in 0..4 {
+            assert_eq!(json["accessors"][accessor]["count"].as_u64().unwrap() as usize, vertex_count);
+        }
+
+        let positions = floats(&json, bin, 0);
+        let tex_coords = floats(&json, bin, 2);
+        assert_eq!(positions[..mesh.positions.len()], mesh.positions[..]);
+        assert!(tex_coords.iter().all(|t| (0.0..=1.0).contains(t)));
+
+        // No triangle spans more than one segment of texture space.
+        let indices = indices(&json, bin);
+        assert_eq!(indices.len(), mesh.indices.len());
+        for triangle in indices.chunks_exact(3) {
+            for axis in 0..2 {
+                let values = triangle.iter().map(|&i| tex_coords[i * 2 + axis]);
+                let spread = values.clone().fold(f32::MIN, f32::max) - values.fold(f32::MAX, f32::min);
+                assert!(spread <= 1.0 / nv.min(nu) as f32 + 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn spin_becomes_a_looping_animation() {
+        let mesh = tessellate(&TorusGeometry::default(), 4, 3);
+        let glb = to_glb(&mesh, "torus", Some(Spin::default())).unwrap();
+        let (json, bin) = chunks(&glb);
+
+        let sampler = &json["animations"][0]["samplers"][0];
+        let times = floats(&json, bin, sampler["input"].as_u64().unwrap() as usize);
+        let rotations = floats(&json, bin, sampler["output"].as_u64().unwrap() as usize);
+        assert_eq!(times.len(), SPIN_KEYFRAMES + 1);
+        assert_eq!(rotations.len(), 4 * times.len());
+        assert_eq!(*times.last().unwrap(), 2.0 * PI);
+    }
+
+    #[test]
+    fn rejects_non_finite_vertices() {
+        let mut mesh = tessellate(&TorusGeometry::default(), 4, 3);
+        mesh.positions[7] = f32::NAN;
+        assert_eq!(to_glb(&mesh, "torus", None), Err(GltfError::NonFinite { attribute: "position", vertex: 2 }));
+
+        mesh.positions[7] = 0.0;
+        mesh.normals[3] = f32::INFINITY;
+        assert_eq!(to_glb(&mesh, "torus", None), Err(GltfError::NonFinite { attribute: "normal", vertex: 1 }));
+    }
+
+    #[test]
+    fn every_catalog_surface_exports() {
+        for name in catalog::NAMES {
+            let mesh = tessellate(catalog::by_name(name).unwrap().as_ref(), 24, 24);
+            assert!(to_glb(&mesh, name, None).is_ok(), "{}", name);
+        }
+    }
+}
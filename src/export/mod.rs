@@ -0,0 +1,11 @@
+//! File formats for getting surfaces out of the crate. Mesh writers take a
+//! [`Mesh`](crate::parametric_surfaces::mesh::Mesh) and image writers raw RGBA pixels; none need a
+//! GL context.
+
+pub mod gltf;
+pub mod obj;
+pub mod ply;
+pub mod png;
+mod seams;
+pub mod stl;
+mod zlib;
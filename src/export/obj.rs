@@ -0,0 +1,90 @@
+use super::seams::UnfoldedSeams;
+use crate::parametric_surfaces::mesh::Mesh;
+use std::fmt::Write;
+
+/// Writes `mesh` as a Wavefront OBJ object called `name`, with positions, normals, texture
+/// coordinates and `v/vt/vn` faces. Corners across the seams of periodic surfaces get their own
+/// texture coordinates, shifted by one so the UV layout stays unfolded.
+pub fn to_obj(mesh: &Mesh, name: &str) -> String {
+    let mut obj = String::new();
+    let UnfoldedSeams { tex_coords, corners } = UnfoldedSeams::new(mesh);
+
+    // Writing to a `String` can't fail.
+    writeln!(obj, "# Generated by parametric_surfaces").unwrap();
+    writeln!(obj, "# {} vertices, {} triangles", mesh.vertex_count(), mesh.triangle_count()).unwrap();
+    writeln!(obj, "o {}", name).unwrap();
+
+    for p in mesh.positions.chunks_exact(3) {
+        writeln!(obj, "v {} {} {}", p[0], p[1], p[2]).unwrap();
+    }
+    for [u, v] in &tex_coords {
+        writeln!(obj, "vt {} {}", u, v).unwrap();
+    }
+    for n in mesh.normals.chunks_exact(3) {
+        writeln!(obj, "vn {} {} {}", n[0], n[1], n[2]).unwrap();
+    }
+
+    // OBJ indices are 1-based.
+    for face in corners.chunks_exact(3) {
+        obj.push('f');
+        for &(v, t) in face {
+            write!(obj, " {}/{}/{}", v + 1, t + 1, v + 1).unwrap();
+        }
+        obj.push('\n');
+    }
+
+    obj
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parametric_surfaces::mesh::tessellate;
+    use crate::parametric_surfaces::torus::TorusGeometry;
+    use crate::parametric_surfaces::ParametricSurface;
+    use nalgebra_glm as glm;
+
+    struct Plane;
+
+    impl ParametricSurface for Plane {
+        fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+            glm::vec3(u, v, 0.0)
+        }
+
+        fn u_domain(&self) -> (f32, f32) {
+            (0.0, 1.0)
+        }
+
+        fn v_domain(&self) -> (f32, f32) {
+            (0.0, 1.0)
+        }
+    }
+
+    fn tex_coords(obj: &str) -> Vec<[f32; 2]> {
+        obj.lines()
+            .filter_map(|line| line.strip_prefix("vt "))
+            .map(|line| {
+                let mut uv = line.split(' ').map(|x| x.parse().unwrap());
+                [uv.next().unwrap(), uv.next().unwrap()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn open_surfaces_keep_their_texture_coordinates() {
+        let obj = to_obj(&tessellate(&Plane, 1, 1), "plane");
+        assert_eq!(tex_coords(&obj), vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]);
+    }
+
+    #[test]
+    fn periodic_seams_get_shifted_texture_coordinates() {
+        let (nu, nv) = (4, 3);
+        let mesh = tessellate(&TorusGeometry::default(), nu, nv);
+        let tex_coords = tex_coords(&to_obj(&mesh, "torus"));
+
+        // A copy of column 0 at u = 1, of row 0 at v = 1, and the corner at (1, 1).
+        assert_eq!(tex_coords.len(), mesh.vertex_count() + nv + nu + 1);
+        assert!(tex_coords.iter().all(|&[u, v]| (0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v)));
+        assert!(tex_coords.contains(&[1.0, 1.0]));
+    }
+}
@@ -0,0 +1,278 @@
+use crate::parametric_surfaces::mesh::{self, Mesh};
+use crate::parametric_surfaces::ParametricSurface;
+use std::fmt::{self, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlyError {
+    /// A property doesn't have exactly one value per vertex.
+    LengthMismatch { property: String, expected: usize, found: usize },
+    /// Property names can't be empty or contain whitespace.
+    InvalidName(String),
+    DuplicateName(String),
+}
+
+impl fmt::Display for PlyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch { property, expected, found } => write!(
+                f, "property '{}' has {} value(s) but the mesh has {} vertices", property, found, expected
+            ),
+            Self::InvalidName(name) => write!(f, "invalid property name '{}'", name),
+            Self::DuplicateName(name) => write!(f, "property '{}' is defined more than once", name),
+        }
+    }
+}
+
+/// Builds a PLY file of a mesh with any number of extra per-vertex float properties, e.g.
+///
+/// ```ignore
+/// let ply = PlyBuilder::new(&mesh)
+///     .format(PlyFormat::BinaryLittleEndian)
+///     .parameters()
+///     .gaussian_curvature(&surface)
+///     .property("height", heights)
+///     .build()?;
+/// ```
+///
+/// Vertices always carry `x y z`, followed by `nx ny nz` unless disabled, then the extra
+/// properties in the order they were added. Faces are written as `vertex_indices` lists.
+pub struct PlyBuilder<'a> {
+    mesh: &'a Mesh,
+    format: PlyFormat,
+    normals: bool,
+    properties: Vec<(String, Vec<f32>)>,
+}
+
+impl<'a> PlyBuilder<'a> {
+    pub fn new(mesh: &'a Mesh) -> Self {
+        Self { mesh, format: PlyFormat::Ascii, normals: true, properties: vec![] }
+    }
+
+    pub fn format(mut self, format: PlyFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn normals(mut self, normals: bool) -> Self {
+        self.normals = normals;
+        self
+    }
+
+    /// Adds a float property with one value per vertex.
+    pub fn property(mut self, name: &str, values: Vec<f32>) -> Self {
+        self.properties.push((name.to_string(), values));
+        self
+    }
+
+    /// Adds the (u, v) each vertex was sampled at as `u` and `v`.
+    pub fn parameters(self) -> Self {
+        let u = self.mesh.parameters.iter().step_by(2).copied().collect();
+        let v = self.mesh.parameters.iter().skip(1).step_by(2).copied().collect();
+        self.property("u", u).property("v", v)
+    }
+
+    /// Adds the renderer's vertex colours as float `red`, `green` and `blue`.
+    pub fn colors(self) -> Self {
+        let channel = |k: usize| self.mesh.colors.iter().skip(k).step_by(3).copied().collect();
+        let (red, green, blue) = (channel(0), channel(1), channel(2));
+        self.property("red", red).property("green", green).property("blue", blue)
+    }
+
+    /// Adds `gaussian_curvature`, evaluated on `surface` at each vertex's (u, v).
+    pub fn gaussian_curvature(self, surface: &dyn ParametricSurface) -> Self {
+        let values = self.curvatures(surface).map(|c| c.gaussian).collect();
+        self.property("gaussian_curvature", values)
+    }
+
+    /// Adds `mean_curvature`, evaluated on `surface` at each vertex's (u, v).
+    pub fn mean_curvature(self, surface: &dyn ParametricSurface) -> Self {
+        let values = self.curvatures(surface).map(|c| c.mean).collect();
+        self.property("mean_curvature", values)
+    }
+
+    fn curvatures<'s>(&'s self, surface: &'s dyn ParametricSurface) -> impl Iterator<Item = mesh::Curvature> + 's {
+        self.mesh.parameters.chunks_exact(2).map(move |p| mesh::curvature(surface, p[0], p[1]))
+    }
+
+    pub fn build(&self) -> Result<Vec<u8>, PlyError> {
+        self.validate()?;
+
+        let mut ply = self.header().into_bytes();
+        match self.format {
+            PlyFormat::Ascii => self.write_ascii(&mut ply),
+            PlyFormat::BinaryLittleEndian => self.write_binary(&mut ply),
+        }
+
+        Ok(ply)
+    }
+
+    fn validate(&self) -> Result<(), PlyError> {
+        let expected = self.mesh.vertex_count();
+        let mut names: Vec<&str> = vec!["x", "y", "z"];
+        if self.normals {
+            names.extend(["nx", "ny", "nz"]);
+        }
+
+        for (name, values) in &self.properties {
+            if name.is_empty() || name.chars().any(char::is_whitespace) {
+                return Err(PlyError::InvalidName(name.clone()));
+            }
+            if names.contains(&name.as_str()) {
+                return Err(PlyError::DuplicateName(name.clone()));
+            }
+            if values.len() != expected {
+                return Err(PlyError::LengthMismatch { property: name.clone(), expected, found: values.len() });
+            }
+            names.push(name);
+        }
+
+        Ok(())
+    }
+
+    fn header(&self) -> String {
+        let format = match self.format {
+            PlyFormat::Ascii => "ascii",
+            PlyFormat::BinaryLittleEndian => "binary_little_endian",
+        };
+
+        // Writing to a `String` can't fail.
+        let mut header = String::new();
+        writeln!(header, "ply\nformat {} 1.0", format).unwrap();
+        writeln!(header, "comment Generated by parametric_surfaces").unwrap();
+        writeln!(header, "element vertex {}", self.mesh.vertex_count()).unwrap();
+        for name in self.vertex_property_names() {
+            writeln!(header, "property float {}", name).unwrap();
+        }
+        writeln!(header, "element face {}", self.mesh.triangle_count()).unwrap();
+        writeln!(header, "property list uchar uint vertex_indices\nend_header").unwrap();
+
+        header
+    }
+
+    fn vertex_property_names(&self) -> impl Iterator<Item = &str> {
+        let normals: &[&str] = if self.normals { &["nx", "ny", "nz"] } else { &[] };
+        ["x", "y", "z"].into_iter()
+            .chain(normals.iter().copied())
+            .chain(self.properties.iter().map(|(name, _)| name.as_str()))
+    }
+
+    fn vertex(&self, i: usize) -> impl Iterator<Item = f32> + '_ {
+        let normals = if self.normals { &self.mesh.normals[i * 3..i * 3 + 3] } else { &[] };
+        self.mesh.positions[i * 3..i * 3 + 3].iter()
+            .chain(normals)
+            .chain(self.properties.iter().map(move |(_, values)| &values[i]))
+            .copied()
+    }
+
+    fn write_ascii(&self, ply: &mut Vec<u8>) {
+        let mut body = String::new();
+        for i in 0..self.mesh.vertex_count() {
+            let values: Vec<String> = self.vertex(i).map(|x| x.to_string()).collect();
+            writeln!(body, "{}", values.join(" ")).unwrap();
+        }
+        let indices: Vec<u32> = self.mesh.indices.iter().collect();
+        for triangle in indices.chunks_exact(3) {
+            writeln!(body, "3 {} {} {}", triangle[0], triangle[1], triangle[2]).unwrap();
+        }
+        ply.extend_from_slice(body.as_bytes());
+    }
+
+    fn write_binary(&self, ply: &mut Vec<u8>) {
+        for i in 0..self.mesh.vertex_count() {
+            for x in self.vertex(i) {
+                ply.extend_from_slice(&x.to_le_bytes());
+            }
+        }
+        let indices: Vec<u32> = self.mesh.indices.iter().collect();
+        for triangle in indices.chunks_exact(3) {
+            ply.push(3);
+            for index in triangle {
+                ply.extend_from_slice(&index.to_le_bytes());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parametric_surfaces::mesh::tessellate;
+    use crate::parametric_surfaces::torus::TorusGeometry;
+
+    fn split_header(ply: &[u8]) -> (String, &[u8]) {
+        let end = ply.windows(11).position(|w| w == b"end_header\n").unwrap() + 11;
+        (String::from_utf8(ply[..end].to_vec()).unwrap(), &ply[end..])
+    }
+
+    #[test]
+    fn header_lists_extra_properties_in_order() {
+        let mesh = tessellate(&TorusGeometry::default(), 4, 3);
+        let heights = vec![1.0; mesh.vertex_count()];
+        let ply = PlyBuilder::new(&mesh).parameters().property("height", heights).build().unwrap();
+        let (header, _) = split_header(&ply);
+
+        let properties: Vec<&str> = header.lines().filter_map(|l| l.strip_prefix("property float ")).collect();
+        assert_eq!(properties, ["x", "y", "z", "nx", "ny", "nz", "u", "v", "height"]);
+        assert!(header.starts_with("ply\nformat ascii 1.0\n"));
+        assert!(header.contains(&format!("element vertex {}\n", mesh.vertex_count())));
+        assert!(header.contains(&format!("element face {}\n", mesh.triangle_count())));
+    }
+
+    #[test]
+    fn ascii_body_has_a_line_per_vertex_and_face() {
+        let mesh = tessellate(&TorusGeometry::default(), 4, 3);
+        let ply = PlyBuilder::new(&mesh).normals(false).parameters().build().unwrap();
+        let (_, body) = split_header(&ply);
+        let lines: Vec<&str> = std::str::from_utf8(body).unwrap().lines().collect();
+
+        assert_eq!(lines.len(), mesh.vertex_count() + mesh.triangle_count());
+        let first: Vec<f32> = lines[0].split(' ').map(|x| x.parse().unwrap()).collect();
+        assert_eq!(&first[..3], &mesh.positions[..3]);
+        assert_eq!(&first[3..], &mesh.parameters[..2]);
+        assert!(lines[mesh.vertex_count()..].iter().all(|l| l.starts_with("3 ")));
+    }
+
+    #[test]
+    fn binary_body_is_little_endian() {
+        let mesh = tessellate(&TorusGeometry::default(), 4, 3);
+        let ply = PlyBuilder::new(&mesh).format(PlyFormat::BinaryLittleEndian).build().unwrap();
+        let (header, body) = split_header(&ply);
+
+        assert!(header.contains("format binary_little_endian 1.0\n"));
+        assert_eq!(body.len(), mesh.vertex_count() * 6 * 4 + mesh.triangle_count() * (1 + 3 * 4));
+        assert_eq!(f32::from_le_bytes(body[..4].try_into().unwrap()), mesh.positions[0]);
+        assert_eq!(f32::from_le_bytes(body[12..16].try_into().unwrap()), mesh.normals[0]);
+
+        let faces = &body[mesh.vertex_count() * 24..];
+        let first_index = u32::from_le_bytes(faces[1..5].try_into().unwrap());
+        assert_eq!(faces[0], 3);
+        assert_eq!(first_index, mesh.indices.iter().next().unwrap());
+    }
+
+    #[test]
+    fn invalid_properties_are_rejected() {
+        let mesh = tessellate(&TorusGeometry::default(), 4, 3);
+        let n = mesh.vertex_count();
+
+        let short = PlyBuilder::new(&mesh).property("k", vec![0.0; n - 1]).build();
+        assert_eq!(short, Err(PlyError::LengthMismatch { property: "k".into(), expected: n, found: n - 1 }));
+
+        let spaced = PlyBuilder::new(&mesh).property("mean curvature", vec![0.0; n]).build();
+        assert_eq!(spaced, Err(PlyError::InvalidName("mean curvature".into())));
+
+        let clash = PlyBuilder::new(&mesh).property("nx", vec![0.0; n]).build();
+        assert_eq!(clash, Err(PlyError::DuplicateName("nx".into())));
+
+        let unused_normals = PlyBuilder::new(&mesh).normals(false).property("nx", vec![0.0; n]).build();
+        assert!(unused_normals.is_ok());
+
+        let twice = PlyBuilder::new(&mesh).parameters().parameters().build();
+        assert_eq!(twice, Err(PlyError::DuplicateName("u".into())));
+    }
+}
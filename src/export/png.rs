@@ -0,0 +1,274 @@
+//! PNG and animated PNG (APNG) writers for RGBA frames, such as those read back from a canvas or
+//! rendered by the [`SoftwareBackend`](crate::raster::SoftwareBackend).
+
+use super::zlib;
+use std::fmt;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PngError {
+    /// An APNG needs at least one frame, which doubles as the still image.
+    NoFrames,
+}
+
+impl fmt::Display for PngError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoFrames => write!(f, "an animated PNG needs at least one frame"),
+        }
+    }
+}
+
+impl std::error::Error for PngError {}
+
+/// PNG of one frame of RGBA pixels, top row first.
+///
+/// # Panics
+///
+/// If `rgba` doesn't hold exactly `width * height` pixels.
+pub fn encode(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let mut png = header(width, height);
+    write_chunk(&mut png, b"IDAT", &zlib::compress(&filter(width, height, rgba)));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// Looping APNG of `frames`, each shown for `1 / fps` seconds. Viewers without APNG support show
+/// the first frame.
+///
+/// # Panics
+///
+/// If any frame doesn't hold exactly `width * height` RGBA pixels.
+pub fn encode_animated(width: u32, height: u32, frames: &[Vec<u8>], fps: u16) -> Result<Vec<u8>, PngError> {
+    if frames.is_empty() {
+        return Err(PngError::NoFrames);
+    }
+
+    let mut png = header(width, height);
+
+    let mut animation_control = Vec::with_capacity(8);
+    animation_control.extend((frames.len() as u32).to_be_bytes());
+    animation_control.extend(0u32.to_be_bytes()); // loop forever
+    write_chunk(&mut png, b"acTL", &animation_control);
+
+    // fcTL and fdAT chunks share one sequence.
+    let mut sequence = 0u32;
+    for (i, frame) in frames.iter().enumerate() {
+        let mut frame_control = Vec::with_capacity(26);
+        frame_control.extend(sequence.to_be_bytes());
+        frame_control.extend(width.to_be_bytes());
+        frame_control.extend(height.to_be_bytes());
+        frame_control.extend(0u32.to_be_bytes()); // x offset
+        frame_control.extend(0u32.to_be_bytes()); // y offset
+        frame_control.extend(1u16.to_be_bytes()); // delay numerator
+        frame_control.extend(fps.max(1).to_be_bytes()); // delay denominator
+        frame_control.push(0); // APNG_DISPOSE_OP_NONE
+        frame_control.push(0); // APNG_BLEND_OP_SOURCE
+        write_chunk(&mut png, b"fcTL", &frame_control);
+        sequence += 1;
+
+        let data = zlib::compress(&filter(width, height, frame));
+        if i == 0 {
+            write_chunk(&mut png, b"IDAT", &data);
+        } else {
+            let mut frame_data = Vec::with_capacity(data.len() + 4);
+            frame_data.extend(sequence.to_be_bytes());
+            frame_data.extend(data);
+            write_chunk(&mut png, b"fdAT", &frame_data);
+            sequence += 1;
+        }
+    }
+
+    write_chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
+
+/// Signature and IHDR for 8-bit RGBA.
+fn header(width: u32, height: u32) -> Vec<u8> {
+    let mut png = SIGNATURE.to_vec();
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend(width.to_be_bytes());
+    ihdr.extend(height.to_be_bytes());
+    ihdr.extend([8, 6, 0, 0, 0]); // bit depth, colour type RGBA, compression, filter, interlace
+    write_chunk(&mut png, b"IHDR", &ihdr);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = zlib::crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// Prefixes each scanline with the filter that leaves it with the smallest sum of absolute
+/// differences, the usual heuristic for what will compress best.
+fn filter(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let stride = width as usize * 4;
+    assert_eq!(rgba.len(), stride * height as usize, "expected {}x{} RGBA pixels", width, height);
+
+    let zeros = vec![0; stride];
+    let mut filtered = Vec::with_capacity((stride + 1) * height as usize);
+    let mut candidate = vec![0; stride];
+    let mut best = vec![0; stride];
+
+    for (y, row) in rgba.chunks_exact(stride.max(1)).enumerate() {
+        let above = if y == 0 { &zeros[..] } else { &rgba[(y - 1) * stride..y * stride] };
+        let mut best_filter = 0;
+        let mut best_cost = u64::MAX;
+
+        for filter_type in 0..5 {
+            for i in 0..stride {
+                let left = if i >= 4 { row[i - 4] } else { 0 };
+                let upper_left = if i >= 4 { above[i - 4] } else { 0 };
+                let predicted = match filter_type {
+                    0 => 0,
+                    1 => left,
+                    2 => above[i],
+                    3 => ((left as u16 + above[i] as u16) / 2) as u8,
+                    _ => paeth(left, above[i], upper_left),
+                };
+                candidate[i] = row[i].wrapping_sub(predicted);
+            }
+            let cost = candidate.iter().map(|&b| (b as i8).unsigned_abs() as u64).sum();
+            if cost < best_cost {
+                best_cost = cost;
+                best_filter = filter_type;
+                best.copy_from_slice(&candidate);
+            }
+        }
+
+        filtered.push(best_filter);
+        filtered.extend(&best);
+    }
+
+    filtered
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniz_oxide::inflate::decompress_to_vec_zlib;
+
+    /// (type, data) of each chunk, checking every CRC along the way.
+    fn chunks(png: &[u8]) -> Vec<([u8; 4], Vec<u8>)> {
+        assert_eq!(png[..8], SIGNATURE);
+        let mut chunks = vec![];
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let (body, crc) = rest[4..8 + length + 4].split_at(4 + length);
+            assert_eq!(zlib::crc32(body), u32::from_be_bytes(crc.try_into().unwrap()));
+            chunks.push((body[..4].try_into().unwrap(), body[4..].to_vec()));
+            rest = &rest[12 + length..];
+        }
+        chunks
+    }
+
+    /// Reverses `filter`.
+    fn unfilter(width: u32, filtered: &[u8]) -> Vec<u8> {
+        let stride = width as usize * 4;
+        let mut rgba: Vec<u8> = Vec::with_capacity(filtered.len());
+        for line in filtered.chunks_exact(stride + 1) {
+            let y = rgba.len() / stride;
+            for i in 0..stride {
+                let left = if i >= 4 { rgba[y * stride + i - 4] } else { 0 };
+                let above = if y > 0 { rgba[(y - 1) * stride + i] } else { 0 };
+                let upper_left = if y > 0 && i >= 4 { rgba[(y - 1) * stride + i - 4] } else { 0 };
+                let predicted = match line[0] {
+                    0 => 0,
+                    1 => left,
+                    2 => above,
+                    3 => ((left as u16 + above as u16) / 2) as u8,
+                    4 => paeth(left, above, upper_left),
+                    filter_type => panic!("unknown filter type {}", filter_type),
+                };
+                rgba.push(line[1 + i].wrapping_add(predicted));
+            }
+        }
+        rgba
+    }
+
+    /// A gradient with some noise, so every filter type gets picked somewhere.
+    fn image(width: u32, height: u32, seed: u32) -> Vec<u8> {
+        let mut state = seed;
+        (0..width * height * 4)
+            .map(|i| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                let (x, y) = (i / 4 % width, i / 4 / width);
+                if (state >> 16) & 3 == 0 { (state >> 24) as u8 } else { (x * 7 + y * 3 + i % 4 * 50) as u8 }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn still_images_round_trip() {
+        let (width, height) = (13, 7);
+        let rgba = image(width, height, 1);
+        let chunks = chunks(&encode(width, height, &rgba));
+
+        let kinds = chunks.iter().map(|(kind, _)| kind).collect::<Vec<_>>();
+        assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 13, 0, 0, 0, 7, 8, 6, 0, 0, 0]);
+        assert_eq!(unfilter(width, &decompress_to_vec_zlib(&chunks[1].1).unwrap()), rgba);
+    }
+
+    #[test]
+    fn paeth_picks_the_nearest_neighbour() {
+        assert_eq!(paeth(10, 20, 10), 20);
+        assert_eq!(paeth(20, 10, 10), 20);
+        assert_eq!(paeth(10, 10, 20), 10);
+        assert_eq!(paeth(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn animations_round_trip() {
+        let (width, height) = (5, 4);
+        let frames = (0..3).map(|seed| image(width, height, seed)).collect::<Vec<_>>();
+        let chunks = chunks(&encode_animated(width, height, &frames, 24).unwrap());
+
+        let kinds = chunks.iter().map(|(kind, _)| kind).collect::<Vec<_>>();
+        assert_eq!(kinds, [b"IHDR", b"acTL", b"fcTL", b"IDAT", b"fcTL", b"fdAT", b"fcTL", b"fdAT", b"IEND"]);
+        assert_eq!(chunks[1].1, [0, 0, 0, 3, 0, 0, 0, 0]);
+
+        // fcTL and fdAT sequence numbers count up from 0 without gaps.
+        let sequence = |data: &[u8]| u32::from_be_bytes(data[..4].try_into().unwrap());
+        let numbered = chunks.iter().filter(|(kind, _)| kind == b"fcTL" || kind == b"fdAT");
+        assert!(numbered.map(|(_, data)| sequence(data)).eq(0..5));
+
+        let fctl = &chunks[2].1;
+        assert_eq!(fctl[4..12], [0, 0, 0, 5, 0, 0, 0, 4]);
+        assert_eq!(fctl[20..24], [0, 1, 0, 24]);
+
+        let decoded = chunks.iter().filter_map(|(kind, data)| match kind {
+            b"IDAT" => Some(&data[..]),
+            b"fdAT" => Some(&data[4..]),
+            _ => None,
+        });
+        for (data, frame) in decoded.zip(&frames) {
+            assert_eq!(&unfilter(width, &decompress_to_vec_zlib(data).unwrap()), frame);
+        }
+    }
+
+    #[test]
+    fn animations_need_a_frame() {
+        assert_eq!(encode_animated(4, 4, &[], 30), Err(PngError::NoFrames));
+    }
+}
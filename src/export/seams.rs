@@ -0,0 +1,66 @@
+use crate::parametric_surfaces::mesh::Mesh;
+use std::collections::HashMap;
+
+/// Texture coordinates further apart than this within one triangle straddle a periodic seam. Periodic
+/// directions have at least three segments, so no other triangle spans more than a third.
+const SEAM_THRESHOLD: f32 = 0.5;
+
+/// Texture coordinates of a mesh with the seams of periodic surfaces unfolded.
+///
+/// Periodic surfaces share their seam vertices between the first and last row/column, whose
+/// texture coordinates would then run back from ~1 to 0 across the closing triangles. Those
+/// corners get an extra texture coordinate shifted by one so the UV layout stays unfolded.
+pub struct UnfoldedSeams {
+    /// One per vertex, in vertex order, then the shifted copies.
+    pub tex_coords: Vec<[f32; 2]>,
+    /// `(vertex, tex_coord)` indices of each triangle corner, in index buffer order.
+    pub corners: Vec<(usize, usize)>,
+}
+
+impl UnfoldedSeams {
+    pub fn new(mesh: &Mesh) -> Self {
+        let mut tex_coords: Vec<[f32; 2]> = mesh.tex_coords.chunks_exact(2).map(|t| [t[0], t[1]]).collect();
+        let mut seam_tex_coords = HashMap::new();
+        let mut corners = Vec::with_capacity(mesh.indices.len());
+        let (periodic_u, periodic_v) = mesh.periodic;
+
+        let indices: Vec<usize> = mesh.indices.iter().map(|i| i as usize).collect();
+        for triangle in indices.chunks_exact(3) {
+            let max = |axis: usize| triangle.iter().map(|&i| tex_coords[i][axis]).fold(f32::MIN, f32::max);
+            let (max_u, max_v) = (max(0), max(1));
+
+            for &i in triangle {
+                let [u, v] = tex_coords[i];
+                let shift = [
+                    if periodic_u && max_u - u > SEAM_THRESHOLD { 1.0 } else { 0.0 },
+                    if periodic_v && max_v - v > SEAM_THRESHOLD { 1.0 } else { 0.0 },
+                ];
+                if shift == [0.0, 0.0] {
+                    corners.push((i, i));
+                    continue;
+                }
+
+                let key = (i, shift[0] as u8, shift[1] as u8);
+                let t = *seam_tex_coords.entry(key).or_insert_with(|| {
+                    tex_coords.push([u + shift[0], v + shift[1]]);
+                    tex_coords.len() - 1
+                });
+                corners.push((i, t));
+            }
+        }
+
+        Self { tex_coords, corners }
+    }
+
+    /// The vertex each shifted texture coordinate was copied from, in order, for formats that need
+    /// a whole new vertex per texture coordinate.
+    pub fn copied_vertices(&self, vertex_count: usize) -> Vec<usize> {
+        let mut copied = vec![0; self.tex_coords.len() - vertex_count];
+        for &(v, t) in &self.corners {
+            if t >= vertex_count {
+                copied[t - vertex_count] = v;
+            }
+        }
+        copied
+    }
+}
@@ -0,0 +1,119 @@
+use crate::parametric_surfaces::mesh::{Mesh, TopologyError, Welded};
+use nalgebra_glm as glm;
+use std::fmt::Write;
+
+const HEADER_LEN: usize = 80;
+/// Normal, three vertices and the attribute byte count.
+const TRIANGLE_LEN: usize = 12 * 4 + 2;
+
+/// Writes `mesh` as an ASCII STL solid called `name`, provided it is watertight.
+pub fn to_ascii_stl(mesh: &Mesh, name: &str) -> Result<String, TopologyError> {
+    let triangles = printable_triangles(mesh)?;
+    let mut stl = String::new();
+
+    // Writing to a `String` can't fail.
+    writeln!(stl, "solid {}", name).unwrap();
+    for [a, b, c] in &triangles {
+        let n = facet_normal(a, b, c);
+        writeln!(stl, "  facet normal {:e} {:e} {:e}", n.x, n.y, n.z).unwrap();
+        writeln!(stl, "    outer loop").unwrap();
+        for p in [a, b, c] {
+            writeln!(stl, "      vertex {:e} {:e} {:e}", p.x, p.y, p.z).unwrap();
+        }
+        writeln!(stl, "    endloop").unwrap();
+        writeln!(stl, "  endfacet").unwrap();
+    }
+    writeln!(stl, "endsolid {}", name).unwrap();
+
+    Ok(stl)
+}
+
+/// Writes `mesh` as a little-endian binary STL, provided it is watertight. `name` goes in the
+/// header, truncated to fit.
+pub fn to_binary_stl(mesh: &Mesh, name: &str) -> Result<Vec<u8>, TopologyError> {
+    let triangles = printable_triangles(mesh)?;
+    let mut stl = Vec::with_capacity(HEADER_LEN + 4 + triangles.len() * TRIANGLE_LEN);
+
+    // Headers starting with "solid" confuse readers into parsing the file as ASCII.
+    let mut header = format!("binary stl {}", name).into_bytes();
+    header.resize(HEADER_LEN, 0);
+    stl.extend_from_slice(&header);
+    stl.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+
+    for [a, b, c] in &triangles {
+        for v in [facet_normal(a, b, c), *a, *b, *c] {
+            for x in v.iter() {
+                stl.extend_from_slice(&x.to_le_bytes());
+            }
+        }
+        stl.extend_from_slice(&0u16.to_le_bytes());
+    }
+
+    Ok(stl)
+}
+
+/// Triangle corners of a watertight mesh, skipping triangles that collapse once coincident
+/// vertices are merged.
+fn printable_triangles(mesh: &Mesh) -> Result<Vec<[glm::Vec3; 3]>, TopologyError> {
+    let welded = Welded::new(mesh);
+    welded.check_watertight()?;
+
+    let position = |i: u32| glm::make_vec3(&mesh.positions[i as usize * 3..i as usize * 3 + 3]);
+    Ok(welded.triangles.iter().map(|t| t.map(position)).collect())
+}
+
+fn facet_normal(a: &glm::Vec3, b: &glm::Vec3, c: &glm::Vec3) -> glm::Vec3 {
+    let normal = glm::cross(&(b - a), &(c - a));
+    let length = glm::length(&normal);
+    if length > 0.0 { normal / length } else { glm::vec3(0.0, 0.0, 0.0) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parametric_surfaces::catalog::MobiusStrip;
+    use crate::parametric_surfaces::mesh::tessellate;
+    use crate::parametric_surfaces::torus::TorusGeometry;
+
+    fn torus() -> Mesh {
+        tessellate(&TorusGeometry::default(), 8, 6)
+    }
+
+    #[test]
+    fn binary_layout() {
+        let stl = to_binary_stl(&torus(), "torus").unwrap();
+        let triangles = 8 * 6 * 2;
+        assert_eq!(stl.len(), HEADER_LEN + 4 + triangles * TRIANGLE_LEN);
+        assert!(stl[..HEADER_LEN].starts_with(b"binary stl torus"));
+        assert_eq!(u32::from_le_bytes(stl[HEADER_LEN..HEADER_LEN + 4].try_into().unwrap()), triangles as u32);
+        for facet in stl[HEADER_LEN + 4..].chunks(TRIANGLE_LEN) {
+            assert_eq!(facet[TRIANGLE_LEN - 2..], [0, 0]);
+        }
+    }
+
+    #[test]
+    fn ascii_structure() {
+        let stl = to_ascii_stl(&torus(), "torus").unwrap();
+        let lines: Vec<&str> = stl.lines().map(str::trim).collect();
+        assert_eq!(lines.first(), Some(&"solid torus"));
+        assert_eq!(lines.last(), Some(&"endsolid torus"));
+
+        // Each facet is a fixed block of seven lines.
+        let facets = &lines[1..lines.len() - 1];
+        assert_eq!(facets.len(), 8 * 6 * 2 * 7);
+        for facet in facets.chunks(7) {
+            assert!(facet[0].starts_with("facet normal "));
+            assert_eq!(facet[1], "outer loop");
+            assert!(facet[2..5].iter().all(|line| line.starts_with("vertex ")));
+            assert_eq!(facet[5..], ["endloop", "endfacet"]);
+        }
+    }
+
+    #[test]
+    fn open_meshes_are_refused() {
+        let strip = tessellate(&MobiusStrip::default(), 16, 4);
+        let ascii = to_ascii_stl(&strip, "strip").unwrap_err();
+        assert!(!ascii.boundary_edges.is_empty());
+        assert_eq!(to_binary_stl(&strip, "strip").unwrap_err(), ascii);
+    }
+}
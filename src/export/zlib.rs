@@ -0,0 +1,292 @@
+//! Just enough zlib to write PNGs: a single fixed-Huffman deflate block with LZ77 matching.
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/// How many earlier occurrences of a 3-byte prefix are tried before settling on the best match.
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+    3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+    7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+/// `data` wrapped in a zlib stream.
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window and no preset dictionary.
+    let mut out = vec![0x78, 0x01];
+    out.extend(deflate(data));
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    // Sums are reduced every 5552 bytes, the most that can be added without overflowing.
+    let (a, b) = bytes.chunks(5552).fold((1u32, 0u32), |(mut a, mut b), chunk| {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        (a % MOD, b % MOD)
+    });
+    (b << 16) | a
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 { 0xedb8_8320 ^ (c >> 1) } else { c >> 1 };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    // Final block, fixed Huffman codes.
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+
+    let mut matcher = Matcher::new(data);
+    let mut i = 0;
+    while i < data.len() {
+        match matcher.longest_match(i) {
+            Some((length, distance)) => {
+                writer.write_length(length);
+                writer.write_distance(distance);
+                (i..i + length).for_each(|pos| matcher.insert(pos));
+                i += length;
+            }
+            None => {
+                writer.write_literal(data[i] as u16);
+                matcher.insert(i);
+                i += 1;
+            }
+        }
+    }
+
+    writer.write_literal(256);
+    writer.finish()
+}
+
+/// Hash chains of every position seen so far, keyed by the 3 bytes starting there.
+struct Matcher<'a> {
+    data: &'a [u8],
+    head: Vec<usize>,
+    prev: Vec<usize>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, head: vec![usize::MAX; 1 << HASH_BITS], prev: vec![usize::MAX; WINDOW_SIZE] }
+    }
+
+    fn hash(&self, pos: usize) -> usize {
+        let bytes = &self.data[pos..pos + MIN_MATCH];
+        let key = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        (key.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+    }
+
+    fn insert(&mut self, pos: usize) {
+        if pos + MIN_MATCH <= self.data.len() {
+            let hash = self.hash(pos);
+            self.prev[pos % WINDOW_SIZE] = self.head[hash];
+            self.head[hash] = pos;
+        }
+    }
+
+    /// Longest (length, distance) back-reference for the bytes at `pos`, if any is long enough.
+    fn longest_match(&self, pos: usize) -> Option<(usize, usize)> {
+        if pos + MIN_MATCH > self.data.len() {
+            return None;
+        }
+
+        let max_length = MAX_MATCH.min(self.data.len() - pos);
+        let mut best = (0, 0);
+        let mut candidate = self.head[self.hash(pos)];
+
+        for _ in 0..MAX_CHAIN {
+            // Slots of the chain get reused once they fall out of the window, so stale links can
+            // point anywhere before `pos`.
+            if candidate == usize::MAX || pos - candidate > WINDOW_SIZE {
+                break;
+            }
+            let length = self.data[candidate..]
+                .iter()
+                .zip(&self.data[pos..pos + max_length])
+                .take_while(|(a, b)| a == b)
+                .count();
+            if length > best.0 {
+                best = (length, pos - candidate);
+                if length == max_length {
+                    break;
+                }
+            }
+            candidate = self.prev[candidate % WINDOW_SIZE];
+        }
+
+        if best.0 >= MIN_MATCH { Some(best) } else { None }
+    }
+}
+
+/// Packs bits least significant first, as deflate expects.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write_bits(&mut self, value: u32, count: u32) {
+        self.buffer |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Huffman codes are defined most significant bit first.
+    fn write_code(&mut self, code: u32, length: u32) {
+        self.write_bits(code.reverse_bits() >> (32 - length), length);
+    }
+
+    fn write_literal(&mut self, symbol: u16) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn write_length(&mut self, length: usize) {
+        let code = LENGTH_BASE.partition_point(|&base| base as usize <= length) - 1;
+        self.write_literal(257 + code as u16);
+        self.write_bits((length - LENGTH_BASE[code] as usize) as u32, LENGTH_EXTRA[code] as u32);
+    }
+
+    fn write_distance(&mut self, distance: usize) {
+        let code = DISTANCE_BASE.partition_point(|&base| base as usize <= distance) - 1;
+        self.write_code(code as u32, 5);
+        self.write_bits((distance - DISTANCE_BASE[code] as usize) as u32, DISTANCE_EXTRA[code] as u32);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniz_oxide::inflate::decompress_to_vec_zlib;
+
+    /// Deterministic bytes that barely compress.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 0x2545_f491u32;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect()
+    }
+
+    fn assert_round_trips(data: &[u8]) {
+        let compressed = compress(data);
+        assert_eq!(decompress_to_vec_zlib(&compressed).unwrap(), data);
+    }
+
+    #[test]
+    fn crc32_matches_known_values() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+    }
+
+    #[test]
+    fn adler32_matches_known_values() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        // Long enough for the sums to be reduced several times.
+        assert_eq!(adler32(&[0xff; 100_000]), 0x149a_302c);
+    }
+
+    #[test]
+    fn empty_input_is_a_lone_end_of_block() {
+        assert_eq!(compress(b""), [0x78, 0x01, 0x03, 0x00, 0x00, 0x00, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn header_passes_its_check() {
+        let header = compress(b"abc");
+        assert_eq!((header[0] as u16 * 256 + header[1] as u16) % 31, 0);
+    }
+
+    #[test]
+    fn literals_round_trip() {
+        assert_round_trips(b"a");
+        // Every byte value, so both the 8 and 9 bit literal codes are used.
+        assert_round_trips(&(0..=255).collect::<Vec<u8>>());
+        assert_round_trips(&noise(10_000));
+    }
+
+    #[test]
+    fn matches_round_trip() {
+        assert_round_trips(b"abcabcabcabcabcabcabcabc");
+        // Runs longer than the longest match, overlapping their own source.
+        assert_round_trips(&[7; 1000]);
+        assert_round_trips("the quick brown fox jumps over the lazy dog. ".repeat(200).as_bytes());
+    }
+
+    #[test]
+    fn matches_stay_within_the_window() {
+        // A block repeated past the 32K window, with chain slots reused many times over.
+        let block = noise(20_000);
+        let data = [&block[..], &noise(30_000), &block[..], &block[..]].concat();
+        assert_round_trips(&data);
+    }
+
+    #[test]
+    fn repetitive_data_compresses() {
+        assert!(compress(&[0; 100_000]).len() < 1_000);
+    }
+}
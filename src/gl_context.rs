@@ -1,25 +1,29 @@
+use crate::error::{Error, Result};
 use js_sys::JsString;
-use wasm_bindgen::{JsValue, JsCast};
+use wasm_bindgen::JsCast;
 use web_sys::HtmlCanvasElement;
 use web_sys::WebGlRenderingContext as GL;
 
-pub fn init_gl_context(canvas_id: JsString) -> Result<GL, JsValue> {
+pub fn init_gl_context(canvas_id: JsString) -> Result<GL> {
     let window = web_sys::window()
-        .ok_or_else(|| JsValue::from_str("Failed to get window object."))?;
+        .ok_or_else(|| Error::Context("Failed to get window object.".into()))?;
 
     let document = window.document()
-        .ok_or_else(|| JsValue::from_str("Failed to get document object."))?;
+        .ok_or_else(|| Error::Context("Failed to get document object.".into()))?;
 
     let element_id = String::from(canvas_id);
 
     let element = document
         .get_element_by_id(&element_id)
         .ok_or_else(|| {
-            JsValue::from(format!("Failed to get canvas element with ID '{}'", element_id))
+            Error::Context(format!("Failed to get canvas element with ID '{}'", element_id))
         })?;
 
-    let canvas = element.dyn_into::<HtmlCanvasElement>()?;
-    let gl_context = canvas.get_context("webgl")?.unwrap().dyn_into::<GL>()?; 
+    let canvas = element.dyn_into::<HtmlCanvasElement>()
+        .map_err(|_| Error::Context(format!("Element with ID '{}' is not a canvas", element_id)))?;
+    let gl_context = canvas.get_context("webgl")?
+        .and_then(|context| context.dyn_into::<GL>().ok())
+        .ok_or_else(|| Error::Context("WebGL is not supported by this browser or device.".into()))?;
 
     // Basic global state settings:
     gl_context.enable(GL::DEPTH_TEST);
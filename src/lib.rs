@@ -1,5 +1,15 @@
+pub mod camera;
+#[cfg(feature = "webgl")]
+pub mod error;
+pub mod export;
+#[cfg(feature = "webgl")]
 mod gl_context;
+pub mod lighting;
 mod macros;
-mod parametric_surfaces;
+pub mod parametric_surfaces;
+pub mod raster;
+pub mod renderer;
+#[cfg(feature = "webgl")]
 mod shader;
+#[cfg(feature = "webgl")]
 mod texture;
@@ -0,0 +1,295 @@
+//! Scene lights and surface materials, shaded per pixel with Blinn-Phong by the WebGL shaders and
+//! by the [`raster`](crate::raster) backend.
+
+use nalgebra_glm as glm;
+use std::f32::consts::PI;
+
+/// Lights the shaders have room for; `MAX_LIGHTS` in `lighting.glsl` must match.
+pub const MAX_LIGHTS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LightKind {
+    /// Infinitely far away, lighting everything from `direction`.
+    Directional,
+    /// Shining every way from `position`.
+    Point,
+    /// Shining from `position` in a cone around `direction`.
+    Spot,
+}
+
+impl LightKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "directional" | "sun" => Some(Self::Directional),
+            "point" => Some(Self::Point),
+            "spot" => Some(Self::Spot),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Light {
+    pub kind: LightKind,
+    /// Scene-space position of point and spot lights.
+    pub position: glm::Vec3,
+    /// Scene-space direction directional and spot lights shine in.
+    pub direction: glm::Vec3,
+    pub color: glm::Vec3,
+    /// Quadratic falloff with distance for point and spot lights, none at 0.
+    pub attenuation: f32,
+    /// Half angles of a spot's cone in radians: full intensity within `inner_angle` of
+    /// `direction`, fading out towards `outer_angle`. Without a gap between them the cone has a
+    /// hard edge at `outer_angle`.
+    pub inner_angle: f32,
+    pub outer_angle: f32,
+}
+
+impl Light {
+    pub fn new(kind: LightKind) -> Self {
+        Self {
+            kind,
+            position: glm::vec3(0.0, 0.0, 2.0),
+            direction: glm::vec3(0.0, 0.0, -1.0),
+            color: glm::vec3(1.0, 1.0, 1.0),
+            attenuation: 0.0,
+            inner_angle: PI / 12.0,
+            outer_angle: PI / 8.0,
+        }
+    }
+
+    pub fn directional(direction: glm::Vec3, color: glm::Vec3) -> Self {
+        Self { direction, color, ..Self::new(LightKind::Directional) }
+    }
+
+    /// The light with its position and direction in the view space of `view`, as the shaders
+    /// expect them.
+    pub fn to_view(&self, view: &glm::Mat4) -> Self {
+        let position = view * glm::vec4(self.position.x, self.position.y, self.position.z, 1.0);
+        let direction = view * glm::vec4(self.direction.x, self.direction.y, self.direction.z, 0.0);
+        Self { position: position.xyz(), direction: direction.xyz(), ..*self }
+    }
+}
+
+/// Ambient light plus up to [`MAX_LIGHTS`] lights.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lighting {
+    pub ambient: glm::Vec3,
+    lights: Vec<Light>,
+}
+
+/// A dim ambient term and a white light from the upper left, over the viewer's shoulder.
+impl Default for Lighting {
+    fn default() -> Self {
+        let mut lighting = Self::new(glm::vec3(0.2, 0.2, 0.2));
+        lighting.lights.push(Light::directional(glm::vec3(1.0, -1.0, -1.0), glm::vec3(1.0, 1.0, 1.0)));
+        lighting
+    }
+}
+
+impl Lighting {
+    /// Ambient light only.
+    pub fn new(ambient: glm::Vec3) -> Self {
+        Self { ambient, lights: vec![] }
+    }
+
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    /// Adds a light and returns its index.
+    pub fn add(&mut self, light: Light) -> Result<usize, String> {
+        if self.lights.len() == MAX_LIGHTS {
+            return Err(format!("Can't add more than {} lights", MAX_LIGHTS));
+        }
+        self.lights.push(light);
+        Ok(self.lights.len() - 1)
+    }
+
+    /// Removes the light at `index`; the lights after it move down one index.
+    pub fn remove(&mut self, index: usize) -> Option<Light> {
+        (index < self.lights.len()).then(|| self.lights.remove(index))
+    }
+
+    pub fn clear(&mut self) {
+        self.lights.clear();
+    }
+
+    pub fn light_mut(&mut self, index: usize) -> Option<&mut Light> {
+        self.lights.get_mut(index)
+    }
+
+    /// Every light in the view space of `view`.
+    pub fn in_view(&self, view: &glm::Mat4) -> Vec<Light> {
+        self.lights.iter().map(|light| light.to_view(view)).collect()
+    }
+
+    /// The same lighting with every light in the view space of `view`, as [`Lighting::shade`]
+    /// expects it.
+    pub fn to_view(&self, view: &glm::Mat4) -> Self {
+        Self { ambient: self.ambient, lights: self.in_view(view) }
+    }
+
+    /// Colour of a point of `base_color` with unit `normal` at `position`, both in view space, lit
+    /// by these lights in view space. Mirrors `shade` in `lighting.glsl`.
+    pub fn shade(&self, material: &Material, base_color: &glm::Vec3, normal: &glm::Vec3, position: &glm::Vec3) -> glm::Vec3 {
+        let to_eye = glm::normalize(&-position);
+        let mut color = self.ambient.component_mul(base_color);
+
+        for light in &self.lights {
+            // A zero direction lights nothing rather than turning everything NaN.
+            let direction = light.direction / glm::length(&light.direction).max(1e-4);
+            let mut to_light = -direction;
+            let mut falloff = 1.0;
+            if light.kind != LightKind::Directional {
+                let offset = light.position - position;
+                let distance = glm::length(&offset);
+                to_light = offset / distance.max(1e-4);
+                falloff = 1.0 / (1.0 + light.attenuation * distance * distance);
+            }
+            if light.kind == LightKind::Spot {
+                let (inner, outer) = (light.inner_angle.cos(), light.outer_angle.cos());
+                let cos_angle = glm::dot(&-to_light, &direction);
+                falloff *= if inner > outer {
+                    glm::smoothstep(outer, inner, cos_angle)
+                } else if cos_angle >= outer {
+                    1.0
+                } else {
+                    0.0
+                };
+            }
+
+            let diffuse = glm::dot(normal, &to_light).max(0.0);
+            let mut specular = 0.0;
+            if diffuse > 0.0 {
+                let halfway = glm::normalize(&(to_light + to_eye));
+                specular = glm::dot(normal, &halfway).max(0.0).powf(material.shininess);
+            }
+
+            let lit = material.diffuse.component_mul(base_color) * diffuse + material.specular * specular;
+            color += light.color.component_mul(&lit) * falloff;
+        }
+
+        color
+    }
+}
+
+/// How a surface responds to light.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Material {
+    /// Multiplies the surface's own colour under diffuse light.
+    pub diffuse: glm::Vec3,
+    pub specular: glm::Vec3,
+    /// Blinn-Phong exponent; higher is a smaller, sharper highlight.
+    pub shininess: f32,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            diffuse: glm::vec3(1.0, 1.0, 1.0),
+            specular: glm::vec3(0.3, 0.3, 0.3),
+            shininess: 32.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GREY: glm::Vec3 = glm::Vec3::new(0.5, 0.5, 0.5);
+
+    fn single(light: Light) -> Lighting {
+        let mut lighting = Lighting::new(glm::vec3(0.2, 0.2, 0.2));
+        lighting.add(light).unwrap();
+        lighting
+    }
+
+    fn shade(lighting: &Lighting, normal: glm::Vec3, position: glm::Vec3) -> glm::Vec3 {
+        lighting.shade(&Material::default(), &GREY, &normal, &position)
+    }
+
+    #[test]
+    fn head_on_lights_add_diffuse_and_specular() {
+        let lighting = single(Light::directional(glm::vec3(0.0, 0.0, -1.0), glm::vec3(1.0, 1.0, 1.0)));
+        let color = shade(&lighting, glm::vec3(0.0, 0.0, 1.0), glm::vec3(0.0, 0.0, -2.0));
+        // Ambient 0.2 * 0.5, diffuse 0.5 and the whole 0.3 of specular.
+        assert!(glm::distance(&color, &glm::vec3(0.9, 0.9, 0.9)) < 1e-6);
+    }
+
+    #[test]
+    fn surfaces_facing_away_only_get_ambient() {
+        let lighting = single(Light::directional(glm::vec3(0.0, 0.0, -1.0), glm::vec3(1.0, 1.0, 1.0)));
+        let color = shade(&lighting, glm::vec3(0.0, 0.0, -1.0), glm::vec3(0.0, 0.0, -2.0));
+        assert_eq!(color, glm::vec3(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn point_lights_fall_off_with_distance() {
+        let mut light = Light::new(LightKind::Point);
+        light.position = glm::vec3(0.0, 0.0, 0.0);
+        light.attenuation = 1.0;
+        let lighting = single(light);
+
+        let near = shade(&lighting, glm::vec3(0.0, 0.0, 1.0), glm::vec3(0.0, 0.0, -1.0));
+        let far = shade(&lighting, glm::vec3(0.0, 0.0, 1.0), glm::vec3(0.0, 0.0, -3.0));
+        // 1 / (1 + d²) of the 0.8 a head-on light adds.
+        assert!((near.x - (0.1 + 0.8 / 2.0)).abs() < 1e-6);
+        assert!((far.x - (0.1 + 0.8 / 10.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn spots_with_equal_angles_cut_off_sharply() {
+        let mut light = Light::new(LightKind::Spot);
+        light.position = glm::vec3(0.0, 0.0, 0.0);
+        light.inner_angle = 0.2;
+        light.outer_angle = 0.2;
+        let lighting = single(light);
+
+        let inside = shade(&lighting, glm::vec3(0.0, 0.0, 1.0), glm::vec3(0.0, 0.0, -2.0));
+        let outside = shade(&lighting, glm::vec3(0.0, 0.0, 1.0), glm::vec3(2.0, 0.0, -2.0));
+        assert!((inside.x - 0.9).abs() < 1e-6);
+        assert_eq!(outside, glm::vec3(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn zero_directions_only_get_ambient() {
+        let lighting = single(Light::directional(glm::vec3(0.0, 0.0, 0.0), glm::vec3(1.0, 1.0, 1.0)));
+        let color = shade(&lighting, glm::vec3(0.0, 0.0, 1.0), glm::vec3(0.0, 0.0, -2.0));
+        assert_eq!(color, glm::vec3(0.1, 0.1, 0.1));
+    }
+
+    #[test]
+    fn views_move_lights_into_view_space() {
+        let mut lighting = Lighting::default();
+        lighting.add(Light::new(LightKind::Point)).unwrap();
+        let view = glm::translate(&glm::Mat4::identity(), &glm::vec3(0.0, 0.0, -5.0));
+
+        let in_view = lighting.to_view(&view);
+        assert_eq!(in_view.ambient, lighting.ambient);
+        assert_eq!(in_view.lights()[0].direction, lighting.lights()[0].direction);
+        assert_eq!(in_view.lights()[1].position, glm::vec3(0.0, 0.0, -3.0));
+    }
+
+    #[test]
+    fn lights_are_capped() {
+        let mut lighting = Lighting::new(glm::vec3(0.0, 0.0, 0.0));
+        for _ in 0..MAX_LIGHTS {
+            lighting.add(Light::new(LightKind::Point)).unwrap();
+        }
+        assert!(lighting.add(Light::new(LightKind::Point)).is_err());
+        assert_eq!(lighting.remove(MAX_LIGHTS), None);
+        assert!(lighting.remove(0).is_some());
+    }
+
+    #[cfg(feature = "webgl")]
+    #[test]
+    fn shaders_have_room_for_max_lights() {
+        let glsl = crate::shader::library::source("lighting.glsl").unwrap();
+        let defined = glsl.lines()
+            .find_map(|line| line.trim().strip_prefix("#define MAX_LIGHTS "))
+            .and_then(|value| value.trim().parse::<usize>().ok());
+        assert_eq!(defined, Some(MAX_LIGHTS));
+    }
+}
@@ -1,30 +1,399 @@
 #[macro_export]
-macro_rules! fmt_mat_f32 {
+macro_rules! buf_f32 {
     ( $i:expr ) => (
         {
-            let mat: [[f32; 4]; 4] = *$i.as_ref();
-            mat.into_iter().flatten().collect::<Vec<f32>>()
+            let slice: &[f32] = &$i[..];
+            js_sys::Float32Array::from(slice).buffer()
         }
     );
 }
 
 #[macro_export]
-macro_rules! buf_f32 {
+macro_rules! buf_u16 {
     ( $i:expr ) => (
         {
-            let slice: &[f32] = &$i[..];
-            js_sys::Float32Array::from(slice).buffer()
+            let slice: &[u16] = &$i[..];
+            js_sys::Uint16Array::from(slice).buffer()
         }
     );
 }
 
 #[macro_export]
-macro_rules! buf_u16 {
+macro_rules! buf_u32 {
     ( $i:expr ) => (
         {
-            let slice: &[u16] = &$i[..];
-            js_sys::Uint16Array::from(slice).buffer()
+            let slice: &[u32] = &$i[..];
+            js_sys::Uint32Array::from(slice).buffer()
+        }
+    );
+}
+
+/// `f64` value of a `js_sys::Number` argument, or an `InvalidArgument` error naming the argument if
+/// JS passed something else.
+#[macro_export]
+macro_rules! number_js {
+    ( $x:ident ) => (
+        $crate::error::number(&$x, stringify!($x))
+    );
+}
+
+#[macro_export]
+macro_rules! vec3_js {
+    ( $x:ident, $y:ident, $z:ident ) => (
+        $crate::error::vec3([(&$x, stringify!($x)), (&$y, stringify!($y)), (&$z, stringify!($z))])
+    );
+}
+
+/// Exposes camera controls to JS, forwarding to the given camera field. `bounds` names a method
+/// returning the `Option<Bounds>` that `fit_camera` frames.
+#[macro_export]
+macro_rules! camera_controls {
+    ( $t:ident, $($camera:ident).+, $($bounds:ident).+ ) => (
+        #[wasm_bindgen::prelude::wasm_bindgen]
+        impl $t {
+            /// Rotates the camera for a pointer drag of (dx, dy) pixels.
+            pub fn orbit_camera(&mut self, dx: js_sys::Number, dy: js_sys::Number) -> Result<(), $crate::error::Error> {
+                self.$($camera).+.rotate($crate::number_js!(dx)? as f32, $crate::number_js!(dy)? as f32);
+                Ok(())
+            }
+
+            /// Pans the camera for a pointer drag of (dx, dy) pixels.
+            pub fn pan_camera(&mut self, dx: js_sys::Number, dy: js_sys::Number) -> Result<(), $crate::error::Error> {
+                self.$($camera).+.pan($crate::number_js!(dx)? as f32, $crate::number_js!(dy)? as f32);
+                Ok(())
+            }
+
+            /// Dollies the camera by a wheel event's `deltaY`.
+            pub fn zoom_camera(&mut self, wheel_delta: js_sys::Number) -> Result<(), $crate::error::Error> {
+                self.$($camera).+.dolly($crate::number_js!(wheel_delta)? as f32);
+                Ok(())
+            }
+
+            /// Switches between "orbit" and "arcball" rotation.
+            pub fn set_camera_mode(&mut self, mode: js_sys::JsString) -> Result<(), $crate::error::Error> {
+                let name = String::from(mode);
+                self.$($camera).+.mode = $crate::camera::RotationMode::from_name(&name).ok_or_else(|| {
+                    $crate::error::Error::InvalidArgument(format!("Unknown camera mode, '{}'", name))
+                })?;
+                Ok(())
+            }
+
+            pub fn reset_camera(&mut self) {
+                self.$($camera).+.reset();
+            }
+
+            /// Switches between "perspective" and "orthographic" projection.
+            pub fn set_projection(&mut self, projection: js_sys::JsString) -> Result<(), $crate::error::Error> {
+                let name = String::from(projection);
+                self.$($camera).+.projection = $crate::camera::Projection::from_name(&name).ok_or_else(|| {
+                    $crate::error::Error::InvalidArgument(format!("Unknown projection, '{}'", name))
+                })?;
+                Ok(())
+            }
+
+            /// Sets the vertical field of view in degrees.
+            pub fn set_fov(&mut self, degrees: js_sys::Number) -> Result<(), $crate::error::Error> {
+                let degrees = $crate::number_js!(degrees)? as f32;
+                self.$($camera).+.fov = degrees.clamp(1.0, 179.0).to_radians();
+                Ok(())
+            }
+
+            pub fn set_clip_planes(&mut self, near: js_sys::Number, far: js_sys::Number) -> Result<(), $crate::error::Error> {
+                let near = $crate::number_js!(near)? as f32;
+                let far = $crate::number_js!(far)? as f32;
+                self.$($camera).+.set_clip_planes(near, far).map_err($crate::error::Error::InvalidArgument)
+            }
+
+            /// Frames everything in view for a canvas of the given size.
+            pub fn fit_camera(
+                &mut self, canvas_width: js_sys::Number, canvas_height: js_sys::Number
+            ) -> Result<(), $crate::error::Error> {
+                #[allow(unused_imports)]
+                use $crate::renderer::Drawable;
+
+                let (width, height) = ($crate::number_js!(canvas_width)?, $crate::number_js!(canvas_height)?);
+                let aspect_ratio = (width / height) as f32;
+                if !(aspect_ratio.is_finite() && aspect_ratio > 0.0) {
+                    return Err($crate::error::Error::InvalidArgument(
+                        format!("Can't fit the camera to a {}x{} canvas", width, height)
+                    ));
+                }
+                if let Some(bounds) = self.$($bounds).+() {
+                    self.$($camera).+.fit(&bounds, aspect_ratio);
+                }
+                Ok(())
+            }
+        }
+    );
+}
+
+/// Exposes file exports of the current mesh to JS, reading it from the given `SurfaceRenderer` field.
+/// The type must also have a `surface(&self) -> &dyn ParametricSurface` method.
+#[macro_export]
+macro_rules! mesh_exports {
+    ( $t:ident, $($renderer:ident).+ ) => (
+        #[wasm_bindgen::prelude::wasm_bindgen]
+        impl $t {
+            /// Wavefront OBJ text of the surface as currently tessellated.
+            pub fn export_obj(&self, name: js_sys::JsString) -> js_sys::JsString {
+                let obj = $crate::export::obj::to_obj(self.$($renderer).+.mesh(), &String::from(name));
+                js_sys::JsString::from(obj)
+            }
+
+            /// STL bytes of the surface as currently tessellated, ASCII unless `binary` is set.
+            /// A positive `thickness` first solidifies the surface into a closed shell. Throws an
+            /// `Error` listing the offending edges if the result isn't watertight.
+            pub fn export_stl(
+                &self, name: js_sys::JsString, binary: bool, thickness: js_sys::Number
+            ) -> Result<js_sys::Uint8Array, $crate::error::Error> {
+                let name = String::from(name);
+                let thickness = $crate::number_js!(thickness)? as f32;
+                let solid;
+                let mesh = if thickness > 0.0 {
+                    solid = $crate::parametric_surfaces::mesh::solidify(self.$($renderer).+.mesh(), thickness);
+                    &solid
+                } else {
+                    self.$($renderer).+.mesh()
+                };
+
+                let stl = if binary {
+                    $crate::export::stl::to_binary_stl(mesh, &name)?
+                } else {
+                    $crate::export::stl::to_ascii_stl(mesh, &name)?.into_bytes()
+                };
+                Ok(js_sys::Uint8Array::from(&stl[..]))
+            }
+
+            /// Binary glTF of the surface as currently tessellated, optionally with its spin as a
+            /// looping rotation animation. Throws if a vertex isn't finite.
+            pub fn export_glb(&self, name: js_sys::JsString, animate: bool) -> Result<js_sys::Uint8Array, $crate::error::Error> {
+                let renderer = &self.$($renderer).+;
+                let spin = if animate { Some(renderer.spin()) } else { None };
+                let glb = $crate::export::gltf::to_glb(renderer.mesh(), &String::from(name), spin)?;
+                Ok(js_sys::Uint8Array::from(&glb[..]))
+            }
+
+            /// PLY bytes of the surface as currently tessellated, with any of the extra vertex
+            /// `fields` "uv", "color", "gaussian_curvature" and "mean_curvature".
+            pub fn export_ply(&self, binary: bool, fields: js_sys::Array) -> Result<js_sys::Uint8Array, $crate::error::Error> {
+                use $crate::export::ply::{PlyBuilder, PlyFormat};
+
+                let format = if binary { PlyFormat::BinaryLittleEndian } else { PlyFormat::Ascii };
+                let mut builder = PlyBuilder::new(self.$($renderer).+.mesh()).format(format);
+
+                for field in fields.iter() {
+                    let field = field.as_string().unwrap_or_default();
+                    builder = match field.as_str() {
+                        "uv" => builder.parameters(),
+                        "color" => builder.colors(),
+                        "gaussian_curvature" => builder.gaussian_curvature(self.surface()),
+                        "mean_curvature" => builder.mean_curvature(self.surface()),
+                        _ => return Err($crate::error::Error::InvalidArgument(format!("Unknown PLY field, '{}'", field))),
+                    };
+                }
+
+                let ply = builder.build()?;
+                Ok(js_sys::Uint8Array::from(&ply[..]))
+            }
+        }
+    );
+}
+
+/// Exposes the lights of the given `Lighting` field to JS. Lights are addressed by index, in the
+/// order they were added.
+#[macro_export]
+macro_rules! lighting_controls {
+    ( $t:ident, $($lighting:ident).+ ) => (
+        #[wasm_bindgen::prelude::wasm_bindgen]
+        impl $t {
+            /// Adds a "directional", "point" or "spot" light and returns its index.
+            pub fn add_light(&mut self, kind: js_sys::JsString) -> Result<u32, $crate::error::Error> {
+                let kind = $crate::lighting::LightKind::from_name(&String::from(kind))
+                    .ok_or_else(|| $crate::error::Error::InvalidArgument("Unknown light kind".into()))?;
+                let index = self.$($lighting).+.add($crate::lighting::Light::new(kind))
+                    .map_err($crate::error::Error::InvalidArgument)?;
+                Ok(index as u32)
+            }
+
+            /// Removes a light; the lights after it move down one index.
+            pub fn remove_light(&mut self, index: u32) -> Result<(), $crate::error::Error> {
+                self.$($lighting).+.remove(index as usize)
+                    .map(|_| ())
+                    .ok_or_else(|| $crate::error::Error::InvalidArgument(format!("No light with index {}", index)))
+            }
+
+            pub fn clear_lights(&mut self) {
+                self.$($lighting).+.clear();
+            }
+
+            pub fn light_count(&self) -> u32 {
+                self.$($lighting).+.lights().len() as u32
+            }
+
+            pub fn set_ambient_light(
+                &mut self, r: js_sys::Number, g: js_sys::Number, b: js_sys::Number
+            ) -> Result<(), $crate::error::Error> {
+                self.$($lighting).+.ambient = $crate::vec3_js!(r, g, b)?;
+                Ok(())
+            }
+
+            /// Scene-space position of a point or spot light.
+            pub fn set_light_position(
+                &mut self, index: u32, x: js_sys::Number, y: js_sys::Number, z: js_sys::Number
+            ) -> Result<(), $crate::error::Error> {
+                let position = $crate::vec3_js!(x, y, z)?;
+                self.light_at(index)?.position = position;
+                Ok(())
+            }
+
+            /// Scene-space direction a directional or spot light shines in.
+            pub fn set_light_direction(
+                &mut self, index: u32, x: js_sys::Number, y: js_sys::Number, z: js_sys::Number
+            ) -> Result<(), $crate::error::Error> {
+                let direction = $crate::vec3_js!(x, y, z)?;
+                if direction.iter().all(|&c| c == 0.0) {
+                    return Err($crate::error::Error::InvalidArgument("A light direction can't be zero".to_string()));
+                }
+                self.light_at(index)?.direction = direction;
+                Ok(())
+            }
+
+            pub fn set_light_color(
+                &mut self, index: u32, r: js_sys::Number, g: js_sys::Number, b: js_sys::Number
+            ) -> Result<(), $crate::error::Error> {
+                let color = $crate::vec3_js!(r, g, b)?;
+                self.light_at(index)?.color = color;
+                Ok(())
+            }
+
+            /// Quadratic falloff with distance of a point or spot light, none at 0.
+            pub fn set_light_attenuation(&mut self, index: u32, attenuation: js_sys::Number) -> Result<(), $crate::error::Error> {
+                let attenuation = $crate::number_js!(attenuation)?.max(0.0) as f32;
+                self.light_at(index)?.attenuation = attenuation;
+                Ok(())
+            }
+
+            /// Half angles of a spot light's cone in degrees, fading out from `inner` to `outer`.
+            /// Equal angles give the cone a hard edge.
+            pub fn set_spot_angles(
+                &mut self, index: u32, inner: js_sys::Number, outer: js_sys::Number
+            ) -> Result<(), $crate::error::Error> {
+                let outer = ($crate::number_js!(outer)? as f32).clamp(0.0, 90.0);
+                let inner = ($crate::number_js!(inner)? as f32).clamp(0.0, outer);
+                let light = self.light_at(index)?;
+                light.inner_angle = inner.to_radians();
+                light.outer_angle = outer.to_radians();
+                Ok(())
+            }
+        }
+
+        impl $t {
+            fn light_at(&mut self, index: u32) -> Result<&mut $crate::lighting::Light, $crate::error::Error> {
+                self.$($lighting).+.light_mut(index as usize)
+                    .ok_or_else(|| $crate::error::Error::InvalidArgument(format!("No light with index {}", index)))
+            }
         }
     );
 }
 
+/// Exposes the given `Material` field to JS.
+#[macro_export]
+macro_rules! material_controls {
+    ( $t:ident, $($material:ident).+ ) => (
+        #[wasm_bindgen::prelude::wasm_bindgen]
+        impl $t {
+            pub fn set_material_diffuse(
+                &mut self, r: js_sys::Number, g: js_sys::Number, b: js_sys::Number
+            ) -> Result<(), $crate::error::Error> {
+                self.$($material).+.diffuse = $crate::vec3_js!(r, g, b)?;
+                Ok(())
+            }
+
+            pub fn set_material_specular(
+                &mut self, r: js_sys::Number, g: js_sys::Number, b: js_sys::Number
+            ) -> Result<(), $crate::error::Error> {
+                self.$($material).+.specular = $crate::vec3_js!(r, g, b)?;
+                Ok(())
+            }
+
+            /// Blinn-Phong exponent; higher is a smaller, sharper highlight.
+            pub fn set_material_shininess(&mut self, shininess: js_sys::Number) -> Result<(), $crate::error::Error> {
+                self.$($material).+.shininess = ($crate::number_js!(shininess)? as f32).max(1.0);
+                Ok(())
+            }
+        }
+    );
+}
+
+/// Exposes live shader editing to JS, forwarding to the given `SurfaceRenderer` field.
+#[macro_export]
+macro_rules! shader_editing {
+    ( $t:ident, $($renderer:ident).+ ) => (
+        #[wasm_bindgen::prelude::wasm_bindgen]
+        impl $t {
+            /// Draws with shaders compiled from the given GLSL. On failure throws an `Error` of kind
+            /// "shaderCompile", with its `diagnostics`, and keeps drawing with the current shaders.
+            pub fn set_shader_sources(
+                &mut self, vertex: js_sys::JsString, fragment: js_sys::JsString
+            ) -> Result<(), $crate::error::Error> {
+                self.$($renderer).+.try_set_shader_sources(&String::from(vertex), &String::from(fragment))
+            }
+
+            /// Goes back to the built-in shaders.
+            pub fn reset_shader(&mut self) -> Result<(), $crate::error::Error> {
+                self.$($renderer).+.try_reset_shader()
+            }
+
+            /// `[vertex, fragment]` GLSL of the built-in shaders, as a starting point for editing.
+            pub fn default_shader_sources(&self) -> js_sys::Array {
+                let (vertex, fragment) = $crate::parametric_surfaces::renderer::SurfaceRenderer::default_shader_sources();
+                js_sys::Array::of2(&vertex.into(), &fragment.into())
+            }
+        }
+    );
+}
+
+/// Exposes PNG captures to JS. `capture` names a method rendering the frame at a given size and
+/// `dtheta` to RGBA pixels, top row first.
+#[macro_export]
+macro_rules! frame_captures {
+    ( $t:ident, $($capture:ident).+ ) => (
+        #[wasm_bindgen::prelude::wasm_bindgen]
+        impl $t {
+            /// PNG of the frame at `dtheta`, rendered at `width` x `height` whatever the canvas size.
+            pub fn screenshot(
+                &self, width: u32, height: u32, dtheta: js_sys::Number
+            ) -> Result<js_sys::Uint8Array, $crate::error::Error> {
+                let pixels = self.$($capture).+(width, height, $crate::number_js!(dtheta)? as f32)?;
+                let png = $crate::export::png::encode(width, height, &pixels);
+                Ok(js_sys::Uint8Array::from(&png[..]))
+            }
+
+            /// One PNG per frame of a full revolution split into `frames` steps, in order.
+            pub fn turntable_png(&self, width: u32, height: u32, frames: u32) -> Result<js_sys::Array, $crate::error::Error> {
+                if frames == 0 {
+                    return Err($crate::error::Error::InvalidArgument("A turntable needs at least one frame".into()));
+                }
+                $crate::renderer::turntable(frames)
+                    .map(|dtheta| {
+                        let pixels = self.$($capture).+(width, height, dtheta)?;
+                        let png = $crate::export::png::encode(width, height, &pixels);
+                        Ok(js_sys::Uint8Array::from(&png[..]))
+                    })
+                    .collect()
+            }
+
+            /// Looping APNG of a full revolution split into `frames` steps, played at `fps`.
+            pub fn turntable_apng(
+                &self, width: u32, height: u32, frames: u32, fps: u16
+            ) -> Result<js_sys::Uint8Array, $crate::error::Error> {
+                let frames = $crate::renderer::turntable(frames)
+                    .map(|dtheta| self.$($capture).+(width, height, dtheta))
+                    .collect::<Result<Vec<_>, $crate::error::Error>>()?;
+                let apng = $crate::export::png::encode_animated(width, height, &frames, fps)
+                    .map_err(|e| $crate::error::Error::InvalidArgument(e.to_string()))?;
+                Ok(js_sys::Uint8Array::from(&apng[..]))
+            }
+        }
+    );
+}
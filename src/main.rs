@@ -0,0 +1,431 @@
+//! `parametric-surfaces`: tessellates a built-in or expression-defined surface and writes it to a
+//! mesh or image file, for batch pipelines that have no browser.
+
+use parametric_surfaces::camera::Camera;
+use parametric_surfaces::export::ply::{PlyBuilder, PlyFormat};
+use parametric_surfaces::export::{gltf, obj, png, stl};
+use parametric_surfaces::parametric_surfaces::catalog;
+use parametric_surfaces::parametric_surfaces::expression::ExpressionGeometry;
+use parametric_surfaces::parametric_surfaces::mesh::{self, Bounds, Mesh};
+use parametric_surfaces::parametric_surfaces::torus::TorusGeometry;
+use parametric_surfaces::parametric_surfaces::{ParametricSurface, Spin};
+use parametric_surfaces::raster::{self, SurfaceModel};
+use parametric_surfaces::renderer;
+use std::f32::consts::PI;
+use std::path::{Path, PathBuf};
+use std::io::{self, Write};
+use std::{env, fs, process};
+
+const USAGE: &str = "\
+Usage: parametric-surfaces <SURFACE> -o <FILE> [OPTIONS]
+       parametric-surfaces --expr <X> <Y> <Z> -o <FILE> [OPTIONS]
+       parametric-surfaces --list
+
+Tessellates a built-in surface, or one given by expressions in u, v and t, and writes it as OBJ,
+STL, PLY, binary glTF or PNG.
+
+Options:
+  -o, --output <FILE>        File to write; its extension picks the format unless --format is set
+  -f, --format <FORMAT>      obj, stl, ply, glb, png or apng
+  -r, --resolution <N[xM]>   Quads along u and v [default: 72]
+      --name <NAME>          Object name written to OBJ, STL and glTF [default: the surface's]
+  -l, --list                 Print the built-in surface names
+  -h, --help                 Print this message
+
+Surface parameters:
+      --expr <X> <Y> <Z>     Coordinate expressions, e.g. \"cos(u)*sin(v)\"
+      --u-domain <MIN,MAX>   Range of u for --expr [default: 0,2pi]
+      --v-domain <MIN,MAX>   Range of v for --expr [default: 0,2pi]
+      --time <T>             Value of t in the expressions [default: 0]
+      --major-radius <R>     Torus major radius
+      --minor-radius <R>     Torus tube radius
+
+Mesh formats:
+      --ascii                Text STL or PLY instead of binary
+      --thickness <T>        Thicken into a closed shell before writing STL
+      --fields <LIST>        Extra PLY vertex fields: uv,color,gaussian_curvature,mean_curvature
+      --animate              Include the surface's spin as a glTF animation
+
+Image formats:
+      --size <WxH>           Image size [default: 800x600]
+      --dtheta <RADIANS>     Spin angle of a still PNG [default: 0]
+      --frames <N>           Turntable frames; PNG writes them as FILE_000.png, ... [APNG default: 60]
+      --fps <N>              APNG frame rate [default: 30]";
+
+const DEFAULT_SUBDIVISIONS: usize = 72;
+const DEFAULT_SIZE: (u32, u32) = (800, 600);
+const DEFAULT_TURNTABLE_FRAMES: u32 = 60;
+const DEFAULT_FPS: u16 = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Obj,
+    Stl,
+    Ply,
+    Glb,
+    Png,
+    Apng,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "obj" => Some(Self::Obj),
+            "stl" => Some(Self::Stl),
+            "ply" => Some(Self::Ply),
+            "glb" => Some(Self::Glb),
+            "png" => Some(Self::Png),
+            "apng" => Some(Self::Apng),
+            _ => None,
+        }
+    }
+}
+
+enum Source {
+    Named(String),
+    Expression([String; 3]),
+}
+
+struct Options {
+    source: Source,
+    output: PathBuf,
+    format: Format,
+    subdivisions: (usize, usize),
+    name: Option<String>,
+    u_domain: (f32, f32),
+    v_domain: (f32, f32),
+    time: f32,
+    major_radius: Option<f32>,
+    minor_radius: Option<f32>,
+    ascii: bool,
+    thickness: f32,
+    fields: Vec<String>,
+    animate: bool,
+    size: (u32, u32),
+    dtheta: f32,
+    frames: Option<u32>,
+    fps: u16,
+}
+
+/// What the command line asked for.
+enum Command {
+    Help,
+    List,
+    Generate(Box<Options>),
+}
+
+fn main() {
+    let result = parse_args(env::args().skip(1)).and_then(|command| match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Command::List => {
+            // Stop quietly if the reader goes away, e.g. when piped into `head`.
+            let mut stdout = io::stdout().lock();
+            let _ = catalog::NAMES.iter().try_for_each(|name| writeln!(stdout, "{}", name));
+            Ok(())
+        }
+        Command::Generate(options) => generate(&options),
+    });
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut surface = None;
+    let mut expressions = None;
+    let mut output = None;
+    let mut format = None;
+    let mut options = Options {
+        source: Source::Named(String::new()),
+        output: PathBuf::new(),
+        format: Format::Obj,
+        subdivisions: (DEFAULT_SUBDIVISIONS, DEFAULT_SUBDIVISIONS),
+        name: None,
+        u_domain: (0.0, 2.0 * PI),
+        v_domain: (0.0, 2.0 * PI),
+        time: 0.0,
+        major_radius: None,
+        minor_radius: None,
+        ascii: false,
+        thickness: 0.0,
+        fields: vec![],
+        animate: false,
+        size: DEFAULT_SIZE,
+        dtheta: 0.0,
+        frames: None,
+        fps: DEFAULT_FPS,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("{} needs a value", flag));
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-l" | "--list" => return Ok(Command::List),
+            "-o" | "--output" => output = Some(PathBuf::from(value(&arg)?)),
+            "-f" | "--format" => {
+                let name = value(&arg)?;
+                format = Some(Format::from_name(&name).ok_or_else(|| format!("Unknown format, '{}'", name))?);
+            }
+            "-r" | "--resolution" => options.subdivisions = parse_pair(&value(&arg)?, 'x')?,
+            "--name" => options.name = Some(value(&arg)?),
+            "--expr" => expressions = Some([value(&arg)?, value(&arg)?, value(&arg)?]),
+            "--u-domain" => options.u_domain = parse_pair(&value(&arg)?, ',')?,
+            "--v-domain" => options.v_domain = parse_pair(&value(&arg)?, ',')?,
+            "--time" => options.time = parse_number(&value(&arg)?)?,
+            "--major-radius" => options.major_radius = Some(parse_number(&value(&arg)?)?),
+            "--minor-radius" => options.minor_radius = Some(parse_number(&value(&arg)?)?),
+            "--ascii" => options.ascii = true,
+            "--thickness" => options.thickness = parse_number(&value(&arg)?)?,
+            "--fields" => options.fields = value(&arg)?.split(',').map(str::to_owned).collect(),
+            "--animate" => options.animate = true,
+            "--size" => options.size = parse_pair(&value(&arg)?, 'x')?,
+            "--dtheta" => options.dtheta = parse_number(&value(&arg)?)?,
+            "--frames" => match parse_number(&value(&arg)?)? {
+                0 => return Err("--frames needs at least one frame".to_owned()),
+                frames => options.frames = Some(frames),
+            },
+            "--fps" => options.fps = parse_number(&value(&arg)?)?,
+            flag if flag.starts_with('-') && flag.len() > 1 => return Err(format!("Unknown option, '{}'", flag)),
+            _ if surface.is_none() => surface = Some(arg),
+            _ => return Err(format!("Unexpected argument, '{}'", arg)),
+        }
+    }
+
+    options.source = match (surface, expressions) {
+        (Some(name), None) => Source::Named(name),
+        (None, Some(expressions)) => Source::Expression(expressions),
+        (Some(_), Some(_)) => return Err("Give either a surface name or --expr, not both".to_owned()),
+        (None, None) => return Err(format!("No surface given\n\n{}", USAGE)),
+    };
+    options.output = output.ok_or("No output file given, pass -o <FILE>")?;
+    options.format = match format {
+        Some(format) => format,
+        None => options.output.extension()
+            .and_then(|extension| Format::from_name(&extension.to_string_lossy()))
+            .ok_or("Can't tell the format from the output file name, pass --format")?,
+    };
+    options.subdivisions = (options.subdivisions.0.max(1), options.subdivisions.1.max(1));
+
+    Ok(Command::Generate(Box::new(options)))
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    text.trim().parse().map_err(|_| format!("Invalid number, '{}'", text))
+}
+
+/// Parses "a<separator>b", or a lone "a" as (a, a).
+fn parse_pair<T: std::str::FromStr + Copy>(text: &str, separator: char) -> Result<(T, T), String> {
+    match text.split_once(separator) {
+        Some((a, b)) => Ok((parse_number(a)?, parse_number(b)?)),
+        None => parse_number(text).map(|a| (a, a)),
+    }
+}
+
+fn build_surface(options: &Options) -> Result<(Box<dyn ParametricSurface>, String), String> {
+    match &options.source {
+        Source::Expression([x, y, z]) => {
+            let mut geometry = ExpressionGeometry::new(x, y, z, options.u_domain, options.v_domain)
+                .map_err(|e| format!("Failed to parse {}", e))?;
+            geometry.set_time(options.time);
+            Ok((Box::new(geometry), "surface".to_owned()))
+        }
+        Source::Named(name) if catalog::by_name(name).is_none() => {
+            Err(format!("Unknown parametric surface, '{}'. Pass --list to see them all", name))
+        }
+        // The torus is the only catalog surface with adjustable parameters.
+        Source::Named(name) if options.major_radius.is_some() || options.minor_radius.is_some() => {
+            if !name.trim().eq_ignore_ascii_case("torus") {
+                return Err("--major-radius and --minor-radius only apply to the torus".to_owned());
+            }
+            let mut torus = TorusGeometry::default();
+            torus.r1 = options.major_radius.unwrap_or(torus.r1);
+            torus.r2 = options.minor_radius.unwrap_or(torus.r2);
+            Ok((Box::new(torus), name.clone()))
+        }
+        Source::Named(name) => Ok((catalog::by_name(name).unwrap(), name.clone())),
+    }
+}
+
+fn generate(options: &Options) -> Result<(), String> {
+    let (surface, surface_name) = build_surface(options)?;
+    let name = options.name.clone().unwrap_or(surface_name);
+    let (nu, nv) = options.subdivisions;
+    let mesh = mesh::tessellate(surface.as_ref(), nu, nv);
+
+    match options.format {
+        Format::Obj => write(&options.output, obj::to_obj(&mesh, &name)),
+        Format::Stl => {
+            let solid;
+            let mesh = if options.thickness > 0.0 {
+                solid = mesh::solidify(&mesh, options.thickness);
+                &solid
+            } else {
+                &mesh
+            };
+            let bytes = if options.ascii {
+                stl::to_ascii_stl(mesh, &name).map(String::into_bytes)
+            } else {
+                stl::to_binary_stl(mesh, &name)
+            };
+            let bytes = bytes.map_err(|e| format!("{}; try --thickness to close it", e))?;
+            write(&options.output, bytes)
+        }
+        Format::Ply => {
+            let format = if options.ascii { PlyFormat::Ascii } else { PlyFormat::BinaryLittleEndian };
+            let mut builder = PlyBuilder::new(&mesh).format(format);
+            for field in &options.fields {
+                builder = match field.as_str() {
+                    "uv" => builder.parameters(),
+                    "color" => builder.colors(),
+                    "gaussian_curvature" => builder.gaussian_curvature(surface.as_ref()),
+                    "mean_curvature" => builder.mean_curvature(surface.as_ref()),
+                    _ => return Err(format!("Unknown PLY field, '{}'", field)),
+                };
+            }
+            write(&options.output, builder.build().map_err(|e| e.to_string())?)
+        }
+        Format::Glb => {
+            let spin = if options.animate { Some(Spin::default()) } else { None };
+            write(&options.output, gltf::to_glb(&mesh, &name, spin).map_err(|e| e.to_string())?)
+        }
+        Format::Png => render_png(options, mesh),
+        Format::Apng => {
+            let (width, height) = options.size;
+            let (model, camera) = framed_model(mesh, options.size)?;
+            let frames = renderer::turntable(options.frames.unwrap_or(DEFAULT_TURNTABLE_FRAMES))
+                .map(|dtheta| raster::render_frame(&model, &camera, width, height, dtheta))
+                .collect::<Vec<_>>();
+            let apng = png::encode_animated(width, height, &frames, options.fps).map_err(|e| e.to_string())?;
+            write(&options.output, apng)
+        }
+    }
+}
+
+/// A still PNG, or numbered turntable frames when `--frames` is given.
+fn render_png(options: &Options, mesh: Mesh) -> Result<(), String> {
+    let (width, height) = options.size;
+    let (model, camera) = framed_model(mesh, options.size)?;
+
+    let frames = match options.frames {
+        Some(frames) => frames,
+        None => {
+            let pixels = raster::render_frame(&model, &camera, width, height, options.dtheta);
+            return write(&options.output, png::encode(width, height, &pixels));
+        }
+    };
+
+    let stem = options.output.file_stem().unwrap_or_default().to_string_lossy();
+    let digits = frames.saturating_sub(1).to_string().len().max(3);
+    for (i, dtheta) in renderer::turntable(frames).enumerate() {
+        let path = options.output.with_file_name(format!("{}_{:0width$}.png", stem, i, width = digits));
+        let pixels = raster::render_frame(&model, &camera, width, height, dtheta);
+        write(&path, png::encode(width, height, &pixels))?;
+    }
+
+    Ok(())
+}
+
+/// The mesh ready to rasterise, with a camera framing it however it spins.
+fn framed_model(mesh: Mesh, (width, height): (u32, u32)) -> Result<(SurfaceModel, Camera), String> {
+    if width == 0 || height == 0 {
+        return Err(format!("Can't render a {}x{} image", width, height));
+    }
+
+    // Like the canvas renderer, allow for the surface spinning about the origin.
+    let reach = mesh.positions.chunks_exact(3)
+        .map(|p| (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt())
+        .fold(0.0, f32::max);
+    let mut camera = Camera::default();
+    camera.fit(&Bounds::cube(reach), width as f32 / height as f32);
+
+    Ok((SurfaceModel::from_mesh(mesh), camera))
+}
+
+fn write<C: AsRef<[u8]>>(path: &Path, contents: C) -> Result<(), String> {
+    fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn options(args: &[&str]) -> Options {
+        match parse(args) {
+            Ok(Command::Generate(options)) => *options,
+            Ok(_) => panic!("{:?} didn't ask for a file", args),
+            Err(e) => panic!("{:?} failed to parse: {}", args, e),
+        }
+    }
+
+    fn error(args: &[&str]) -> String {
+        parse(args).err().unwrap_or_else(|| panic!("{:?} should not parse", args))
+    }
+
+    #[test]
+    fn format_comes_from_the_extension_unless_given() {
+        assert_eq!(options(&["torus", "-o", "out.STL"]).format, Format::Stl);
+        assert_eq!(options(&["torus", "-o", "out.glb"]).format, Format::Glb);
+        assert_eq!(options(&["torus", "-o", "out.bin", "--format", "ply"]).format, Format::Ply);
+        assert!(error(&["torus", "-o", "out.bin"]).contains("pass --format"));
+        assert_eq!(error(&["torus", "-o", "out", "-f", "fbx"]), "Unknown format, 'fbx'");
+    }
+
+    #[test]
+    fn pairs_accept_one_or_two_values() {
+        let parsed = options(&["sphere", "-o", "a.obj", "-r", "16x8", "--size", "64", "--u-domain", "-1,2.5"]);
+        assert_eq!(parsed.subdivisions, (16, 8));
+        assert_eq!(parsed.size, (64, 64));
+        assert_eq!(parsed.u_domain, (-1.0, 2.5));
+        assert_eq!(options(&["sphere", "-o", "a.obj", "-r", "0"]).subdivisions, (1, 1));
+        assert_eq!(error(&["sphere", "-o", "a.obj", "-r", "8xy"]), "Invalid number, 'y'");
+    }
+
+    #[test]
+    fn expressions_take_three_values() {
+        let parsed = options(&["--expr", "u", "v", "u*v", "-o", "a.obj", "--time", "0.5"]);
+        assert!(matches!(&parsed.source, Source::Expression(e) if e == &["u", "v", "u*v"]));
+        assert_eq!(parsed.time, 0.5);
+        assert_eq!(error(&["-o", "a.obj", "--expr", "u", "v"]), "--expr needs a value");
+        assert!(error(&["torus", "--expr", "u", "v", "0", "-o", "a.obj"]).contains("not both"));
+    }
+
+    #[test]
+    fn turntables_need_a_frame() {
+        assert_eq!(options(&["torus", "-o", "a.apng", "--frames", "12"]).frames, Some(12));
+        assert_eq!(error(&["torus", "-o", "a.apng", "--frames", "0"]), "--frames needs at least one frame");
+    }
+
+    #[test]
+    fn help_list_and_mistakes() {
+        assert!(matches!(parse(&["torus", "--help"]), Ok(Command::Help)));
+        assert!(matches!(parse(&["-l"]), Ok(Command::List)));
+        assert_eq!(error(&["torus", "-o", "a.obj", "--bogus"]), "Unknown option, '--bogus'");
+        assert_eq!(error(&["torus", "sphere", "-o", "a.obj"]), "Unexpected argument, 'sphere'");
+        assert!(error(&["torus"]).starts_with("No output file given"));
+        assert!(error(&["-o", "a.obj"]).starts_with("No surface given"));
+    }
+
+    #[test]
+    fn radii_only_apply_to_the_torus() {
+        let torus = options(&["Torus", "-o", "a.obj", "--major-radius", "2"]);
+        let (surface, name) = build_surface(&torus).unwrap();
+        assert_eq!(name, "Torus");
+        assert!(surface.position(0.0, 0.0).x > 2.0);
+
+        let sphere = options(&["sphere", "-o", "a.obj", "--minor-radius", "2"]);
+        assert!(build_surface(&sphere).err().unwrap().contains("only apply to the torus"));
+
+        let unknown = options(&["teapot", "-o", "a.obj"]);
+        assert!(build_surface(&unknown).err().unwrap().starts_with("Unknown parametric surface, 'teapot'"));
+    }
+}
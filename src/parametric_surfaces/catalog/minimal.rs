@@ -0,0 +1,95 @@
+use crate::parametric_surfaces::ParametricSurface;
+use nalgebra_glm as glm;
+use std::f32::consts::PI;
+
+/// Helicoid swept by a line of half-length `r` that makes `turns` full turns while rising `height`.
+pub struct Helicoid {
+    pub r: f32,
+    pub turns: f32,
+    pub height: f32,
+}
+
+impl Default for Helicoid {
+    fn default() -> Self {
+        Self { r: 0.6, turns: 1.5, height: 1.2 }
+    }
+}
+
+impl ParametricSurface for Helicoid {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let angle = 2.0 * PI * self.turns * v;
+        glm::vec3(u * angle.cos(), u * angle.sin(), self.height * (v - 0.5))
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (-self.r, self.r)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (0.0, 1.0)
+    }
+}
+
+/// Catenoid with waist radius `c`, truncated at heights ±`height` / 2.
+pub struct Catenoid {
+    pub c: f32,
+    pub height: f32,
+}
+
+impl Default for Catenoid {
+    fn default() -> Self {
+        Self { c: 0.3, height: 0.9 }
+    }
+}
+
+impl ParametricSurface for Catenoid {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let radius = self.c * (v / self.c).cosh();
+        glm::vec3(radius * u.cos(), radius * u.sin(), v)
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (-self.height / 2.0, self.height / 2.0)
+    }
+
+    fn periodic_u(&self) -> bool {
+        true
+    }
+}
+
+/// Enneper's minimal surface in polar form, u the angle and v the radius up to `r`.
+pub struct Enneper {
+    pub r: f32,
+    pub scale: f32,
+}
+
+impl Default for Enneper {
+    fn default() -> Self {
+        Self { r: 1.4, scale: 0.35 }
+    }
+}
+
+impl ParametricSurface for Enneper {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let x = v * u.cos() - v.powi(3) / 3.0 * (3.0 * u).cos();
+        let y = -v * u.sin() - v.powi(3) / 3.0 * (3.0 * u).sin();
+        let z = v.powi(2) * (2.0 * u).cos();
+        self.scale * glm::vec3(x, y, z)
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (0.0, self.r)
+    }
+
+    fn periodic_u(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,129 @@
+mod minimal;
+mod non_orientable;
+mod quadrics;
+mod spirals;
+mod tubes;
+#[cfg(feature = "wasm")]
+mod wasm;
+use crate::parametric_surfaces::torus::TorusGeometry;
+use crate::parametric_surfaces::ParametricSurface;
+
+pub use minimal::{Catenoid, Enneper, Helicoid};
+pub use non_orientable::{BoySurface, KleinBottle, KleinFigureEight, MobiusStrip, RomanSurface};
+pub use quadrics::{Ellipsoid, Sphere};
+pub use spirals::{DiniSurface, Seashell};
+pub use tubes::TrefoilKnot;
+#[cfg(feature = "wasm")]
+pub use wasm::{surface_names, CatalogSurface};
+
+/// Names accepted by [`by_name`], in display order.
+pub const NAMES: [&str; 14] = [
+    "Torus",
+    "Sphere",
+    "Ellipsoid",
+    "Mobius strip",
+    "Klein figure-8",
+    "Klein bottle",
+    "Boy's surface",
+    "Roman surface",
+    "Helicoid",
+    "Catenoid",
+    "Enneper",
+    "Dini's surface",
+    "Seashell",
+    "Trefoil knot",
+];
+
+/// Looks up a built-in surface with its default parameters. Matching ignores case, punctuation
+/// and whitespace, so "Klein bottle", "klein_bottle" and "KleinBottle" are all equivalent.
+pub fn by_name(name: &str) -> Option<Box<dyn ParametricSurface>> {
+    let key: String = name
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    let surface: Box<dyn ParametricSurface> = match key.as_str() {
+        "sphere" => Box::new(Sphere::default()),
+        "ellipsoid" => Box::new(Ellipsoid::default()),
+        "torus" => Box::new(TorusGeometry::default()),
+        "mobius" | "mobiusstrip" | "möbius" | "möbiusstrip" => Box::new(MobiusStrip::default()),
+        "klein8" | "kleinfigure8" | "kleinfigureeight" => Box::new(KleinFigureEight::default()),
+        "klein" | "kleinbottle" => Box::new(KleinBottle::default()),
+        "boy" | "boys" | "boyssurface" => Box::new(BoySurface::default()),
+        "roman" | "romansurface" => Box::new(RomanSurface::default()),
+        "helicoid" => Box::new(Helicoid::default()),
+        "catenoid" => Box::new(Catenoid::default()),
+        "enneper" | "ennepersurface" => Box::new(Enneper::default()),
+        "dini" | "dinis" | "dinissurface" => Box::new(DiniSurface::default()),
+        "seashell" => Box::new(Seashell::default()),
+        "trefoil" | "trefoilknot" => Box::new(TrefoilKnot::default()),
+        _ => return None,
+    };
+
+    Some(surface)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm as glm;
+
+    fn sample(domain: (f32, f32), i: usize, n: usize) -> f32 {
+        domain.0 + (domain.1 - domain.0) * i as f32 / n as f32
+    }
+
+    #[test]
+    fn every_listed_name_resolves() {
+        for name in NAMES {
+            assert!(by_name(name).is_some(), "{name} is not in the catalog");
+        }
+        assert!(by_name("teapot").is_none());
+    }
+
+    #[test]
+    fn names_ignore_case_and_punctuation() {
+        for alias in ["klein_bottle", "KleinBottle", " klein-bottle ", "Möbius strip", "BOYS"] {
+            assert!(by_name(alias).is_some(), "{alias} did not resolve");
+        }
+        let bottle = by_name("Klein bottle").unwrap();
+        let figure_eight = by_name("Klein figure-8").unwrap();
+        assert_ne!(bottle.position(1.0, 1.0), figure_eight.position(1.0, 1.0));
+    }
+
+    #[test]
+    fn default_surfaces_are_finite_over_their_domains() {
+        for name in NAMES {
+            let surface = by_name(name).unwrap();
+            for i in 0..=16 {
+                for j in 0..=16 {
+                    let u = sample(surface.u_domain(), i, 16);
+                    let v = sample(surface.v_domain(), j, 16);
+                    let p = surface.position(u, v);
+                    assert!(p.iter().all(|c| c.is_finite()), "{name} at ({u}, {v}) is {p:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn periodic_flags_match_the_equations() {
+        for name in NAMES {
+            let surface = by_name(name).unwrap();
+            let (u0, u1) = surface.u_domain();
+            let (v0, v1) = surface.v_domain();
+            for i in 0..=8 {
+                if surface.periodic_u() {
+                    let v = sample((v0, v1), i, 8);
+                    let gap = glm::distance(&surface.position(u0, v), &surface.position(u1, v));
+                    assert!(gap < 1e-3, "{name} does not close in u (gap {gap})");
+                }
+                if surface.periodic_v() {
+                    let u = sample((u0, u1), i, 8);
+                    let gap = glm::distance(&surface.position(u, v0), &surface.position(u, v1));
+                    assert!(gap < 1e-3, "{name} does not close in v (gap {gap})");
+                }
+            }
+        }
+    }
+}
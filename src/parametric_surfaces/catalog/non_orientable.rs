@@ -0,0 +1,161 @@
+use crate::parametric_surfaces::ParametricSurface;
+use nalgebra_glm as glm;
+use std::f32::consts::{PI, SQRT_2};
+
+/// Möbius strip of centre radius `r` and strip half-width `w`.
+pub struct MobiusStrip {
+    pub r: f32,
+    pub w: f32,
+}
+
+impl Default for MobiusStrip {
+    fn default() -> Self {
+        Self { r: 0.55, w: 0.2 }
+    }
+}
+
+impl ParametricSurface for MobiusStrip {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let radius = self.r + v * (u / 2.0).cos();
+        glm::vec3(radius * u.cos(), radius * u.sin(), v * (u / 2.0).sin())
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (-self.w, self.w)
+    }
+}
+
+/// Figure-8 immersion of the Klein bottle around a circle of radius `r`.
+pub struct KleinFigureEight {
+    pub r: f32,
+    pub scale: f32,
+}
+
+impl Default for KleinFigureEight {
+    fn default() -> Self {
+        Self { r: 2.0, scale: 0.2 }
+    }
+}
+
+impl ParametricSurface for KleinFigureEight {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let (half_sin, half_cos) = (u / 2.0).sin_cos();
+        let radius = self.r + half_cos * v.sin() - half_sin * (2.0 * v).sin();
+        let z = half_sin * v.sin() + half_cos * (2.0 * v).sin();
+        self.scale * glm::vec3(radius * u.cos(), radius * u.sin(), z)
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn periodic_v(&self) -> bool {
+        true
+    }
+}
+
+/// The classic "bottle" immersion of the Klein bottle, recentred on the origin.
+pub struct KleinBottle {
+    pub scale: f32,
+}
+
+impl Default for KleinBottle {
+    fn default() -> Self {
+        Self { scale: 0.3 }
+    }
+}
+
+impl ParametricSurface for KleinBottle {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let (su, cu) = u.sin_cos();
+        let (sv, cv) = v.sin_cos();
+
+        let x = -2.0 / 15.0 * cu * (
+            3.0 * cv - 30.0 * su + 90.0 * cu.powi(4) * su - 60.0 * cu.powi(6) * su + 5.0 * cu * cv * su
+        );
+        let y = -1.0 / 15.0 * su * (
+            3.0 * cv - 3.0 * cu.powi(2) * cv - 48.0 * cu.powi(4) * cv + 48.0 * cu.powi(6) * cv
+            - 60.0 * su + 5.0 * cu * cv * su - 5.0 * cu.powi(3) * cv * su
+            - 80.0 * cu.powi(5) * cv * su + 80.0 * cu.powi(7) * cv * su
+        );
+        let z = 2.0 / 15.0 * (3.0 + 5.0 * cu * su) * sv;
+
+        self.scale * glm::vec3(x, y - 2.0, z)
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, PI)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn periodic_v(&self) -> bool {
+        true
+    }
+}
+
+/// Boy's surface using Apéry's parametrisation, recentred on the origin.
+pub struct BoySurface {
+    pub scale: f32,
+}
+
+impl Default for BoySurface {
+    fn default() -> Self {
+        Self { scale: 0.35 }
+    }
+}
+
+impl ParametricSurface for BoySurface {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let denominator = 2.0 - SQRT_2 * (3.0 * u).sin() * (2.0 * v).sin();
+        let x = SQRT_2 * (2.0 * u).cos() * v.cos().powi(2) + u.cos() * (2.0 * v).sin();
+        let y = SQRT_2 * (2.0 * u).sin() * v.cos().powi(2) - u.sin() * (2.0 * v).sin();
+        let z = 3.0 * v.cos().powi(2);
+        self.scale * glm::vec3(x / denominator, y / denominator, z / denominator - 1.5)
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (-PI / 2.0, PI / 2.0)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (0.0, PI)
+    }
+}
+
+/// Steiner's Roman surface, the image of a hemisphere under (x, y, z) -> (yz, zx, xy).
+pub struct RomanSurface {
+    pub r: f32,
+}
+
+impl Default for RomanSurface {
+    fn default() -> Self {
+        Self { r: 1.2 }
+    }
+}
+
+impl ParametricSurface for RomanSurface {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let r2 = self.r * self.r;
+        let (x, y, z) = (v.sin() * u.cos(), v.sin() * u.sin(), v.cos());
+        r2 * glm::vec3(y * z, z * x, x * y)
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, PI)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (0.0, PI)
+    }
+}
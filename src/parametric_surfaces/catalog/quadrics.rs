@@ -0,0 +1,75 @@
+use crate::parametric_surfaces::ParametricSurface;
+use nalgebra_glm as glm;
+use std::f32::consts::PI;
+
+/// Sphere of radius `r`, u around the z-axis and v from the south to the north pole.
+pub struct Sphere {
+    pub r: f32,
+}
+
+impl Default for Sphere {
+    fn default() -> Self {
+        Self { r: 0.6 }
+    }
+}
+
+impl ParametricSurface for Sphere {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        self.r * glm::vec3(v.sin() * u.cos(), v.sin() * u.sin(), -v.cos())
+    }
+
+    fn partials(&self, u: f32, v: f32) -> Option<(glm::Vec3, glm::Vec3)> {
+        let du = self.r * glm::vec3(-v.sin() * u.sin(), v.sin() * u.cos(), 0.0);
+        let dv = self.r * glm::vec3(v.cos() * u.cos(), v.cos() * u.sin(), v.sin());
+        Some((du, dv))
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (0.0, PI)
+    }
+
+    fn periodic_u(&self) -> bool {
+        true
+    }
+}
+
+/// Ellipsoid with semi-axes `a`, `b` and `c` along x, y and z.
+pub struct Ellipsoid {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+}
+
+impl Default for Ellipsoid {
+    fn default() -> Self {
+        Self { a: 0.7, b: 0.5, c: 0.35 }
+    }
+}
+
+impl ParametricSurface for Ellipsoid {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        glm::vec3(self.a * v.sin() * u.cos(), self.b * v.sin() * u.sin(), -self.c * v.cos())
+    }
+
+    fn partials(&self, u: f32, v: f32) -> Option<(glm::Vec3, glm::Vec3)> {
+        let du = glm::vec3(-self.a * v.sin() * u.sin(), self.b * v.sin() * u.cos(), 0.0);
+        let dv = glm::vec3(self.a * v.cos() * u.cos(), self.b * v.cos() * u.sin(), self.c * v.sin());
+        Some((du, dv))
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (0.0, PI)
+    }
+
+    fn periodic_u(&self) -> bool {
+        true
+    }
+}
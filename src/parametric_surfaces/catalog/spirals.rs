@@ -0,0 +1,70 @@
+use crate::parametric_surfaces::ParametricSurface;
+use nalgebra_glm as glm;
+use std::f32::consts::{E, PI};
+
+/// Dini's surface, a twisted pseudosphere. `a` is the radius of the tractrix and `b` the rise per
+/// radian of twist. Two full twists are drawn, recentred on the origin.
+pub struct DiniSurface {
+    pub a: f32,
+    pub b: f32,
+    pub scale: f32,
+}
+
+impl Default for DiniSurface {
+    fn default() -> Self {
+        Self { a: 1.0, b: 0.2, scale: 0.3 }
+    }
+}
+
+impl ParametricSurface for DiniSurface {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let x = self.a * u.cos() * v.sin();
+        let y = self.a * u.sin() * v.sin();
+        let z = self.a * (v.cos() + (v / 2.0).tan().ln()) + self.b * (u - 2.0 * PI);
+        self.scale * glm::vec3(x, y, z + self.a)
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, 4.0 * PI)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (0.1, 2.0)
+    }
+}
+
+/// Logarithmic spiral shell making `turns` whorls, recentred on the origin.
+pub struct Seashell {
+    pub turns: f32,
+    pub scale: f32,
+}
+
+impl Default for Seashell {
+    fn default() -> Self {
+        Self { turns: 3.0, scale: 0.17 }
+    }
+}
+
+impl ParametricSurface for Seashell {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let growth = (u / (2.0 * self.turns * PI)).exp();
+        let profile = (v / 2.0).cos().powi(2);
+        let x = 2.0 * (1.0 - growth) * u.cos() * profile;
+        let y = 2.0 * (growth - 1.0) * u.sin() * profile;
+        let z = 1.0 - growth * growth - v.sin() + growth * v.sin();
+        // Growth runs from 1 to e, putting z in [2 - e² - e, 0].
+        self.scale * glm::vec3(x, y, z + (E * E + E - 2.0) / 2.0)
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * self.turns * PI)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn periodic_v(&self) -> bool {
+        true
+    }
+}
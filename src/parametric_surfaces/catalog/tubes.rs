@@ -0,0 +1,58 @@
+use crate::parametric_surfaces::ParametricSurface;
+use nalgebra_glm as glm;
+use std::f32::consts::PI;
+
+/// Tube of radius `r` swept along a trefoil knot, u along the knot and v around the tube.
+pub struct TrefoilKnot {
+    pub r: f32,
+    pub scale: f32,
+}
+
+impl Default for TrefoilKnot {
+    fn default() -> Self {
+        Self { r: 0.4, scale: 0.2 }
+    }
+}
+
+impl TrefoilKnot {
+    fn curve(u: f32) -> glm::Vec3 {
+        glm::vec3(u.sin() + 2.0 * (2.0 * u).sin(), u.cos() - 2.0 * (2.0 * u).cos(), -(3.0 * u).sin())
+    }
+
+    fn tangent(u: f32) -> glm::Vec3 {
+        glm::vec3(u.cos() + 4.0 * (2.0 * u).cos(), -u.sin() + 4.0 * (2.0 * u).sin(), -3.0 * (3.0 * u).cos())
+    }
+
+    fn acceleration(u: f32) -> glm::Vec3 {
+        glm::vec3(-u.sin() - 8.0 * (2.0 * u).sin(), -u.cos() + 8.0 * (2.0 * u).cos(), 9.0 * (3.0 * u).sin())
+    }
+}
+
+impl ParametricSurface for TrefoilKnot {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        // The trefoil's curvature never vanishes, so its Frenet frame is well defined and closes up.
+        let tangent = glm::normalize(&Self::tangent(u));
+        let acceleration = Self::acceleration(u);
+        let normal = glm::normalize(&(acceleration - tangent * glm::dot(&acceleration, &tangent)));
+        let binormal = glm::cross(&tangent, &normal);
+        let offset = normal * v.cos() + binormal * v.sin();
+
+        self.scale * (Self::curve(u) + offset * self.r)
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        (0.0, 2.0 * PI)
+    }
+
+    fn periodic_u(&self) -> bool {
+        true
+    }
+
+    fn periodic_v(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,53 @@
+use super::{by_name, NAMES};
+use crate::error::Error;
+use crate::{camera_controls, frame_captures, lighting_controls, material_controls, mesh_exports, shader_editing};
+use crate::parametric_surfaces::renderer::SurfaceRenderer;
+use crate::parametric_surfaces::ParametricSurface;
+use js_sys::{Array, JsString, Number};
+use wasm_bindgen::JsValue;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Names of the built-in surfaces, for populating UI selectors.
+#[wasm_bindgen]
+pub fn surface_names() -> Array {
+    NAMES.iter().map(|&name| JsValue::from_str(name)).collect()
+}
+
+/// Any built-in surface, selected by name.
+#[wasm_bindgen]
+pub struct CatalogSurface {
+    surface: Box<dyn ParametricSurface>,
+    renderer: SurfaceRenderer,
+}
+
+type CatalogResult<T> = Result<T, Error>;
+
+#[wasm_bindgen]
+impl CatalogSurface {
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas_id: JsString, name: JsString) -> CatalogResult<CatalogSurface> {
+        let name = String::from(name);
+        let surface = by_name(&name).ok_or(Error::UnknownSurface(name))?;
+        let renderer = SurfaceRenderer::try_new(canvas_id, surface.as_ref())?;
+
+        Ok(Self { surface, renderer })
+    }
+
+    #[wasm_bindgen]
+    pub fn render(&self, canvas_width: Number, canvas_height: Number, dtheta: Number) -> CatalogResult<()> {
+        self.renderer.try_render(canvas_width, canvas_height, dtheta)
+    }
+}
+
+impl CatalogSurface {
+    fn surface(&self) -> &dyn ParametricSurface {
+        self.surface.as_ref()
+    }
+}
+
+camera_controls!(CatalogSurface, renderer.camera, renderer.bounds);
+mesh_exports!(CatalogSurface, renderer);
+shader_editing!(CatalogSurface, renderer);
+lighting_controls!(CatalogSurface, renderer.lighting);
+material_controls!(CatalogSurface, renderer.material);
+frame_captures!(CatalogSurface, renderer.capture);
@@ -0,0 +1,124 @@
+use super::{geometry, nested_cubes};
+use crate::camera::Camera;
+use crate::error::{number, Error};
+use crate::parametric_surfaces::mesh::Bounds;
+use crate::renderer::{self, Drawable, Frame};
+use crate::{gl_context, shader, buf_f32};
+use js_sys::{JsString, Number};
+use nalgebra_glm as glm;
+use std::rc::Rc;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+use web_sys::WebGlBuffer as Buffer;
+use web_sys::WebGlRenderingContext as GL;
+
+type CubeResult<T> = Result<T, Error>;
+
+/// Distance of the camera from the cube when drawn on its own.
+const CAMERA_DISTANCE: f32 = 2.5;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct Cube {
+    camera: Camera,
+    gl: GL,
+    cube_shader: Rc<shader::Shader>,
+    positions_buffer: Buffer,
+    position_attr: u32,
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+impl Cube {
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas_id: JsString) -> CubeResult<Cube> {
+        Self::try_new(canvas_id)
+    }
+
+    #[wasm_bindgen]
+    pub fn render(&self, canvas_width: Number, canvas_height: Number, dtheta: Number) -> CubeResult<()> {
+        self.try_render(canvas_width, canvas_height, dtheta)
+    }
+}
+
+impl Cube {
+    pub fn try_new(canvas_id: JsString) -> CubeResult<Self> {
+        let gl = gl_context::init_gl_context(canvas_id)?;
+        Self::try_with_context(gl)
+    }
+
+    pub fn try_render(&self, canvas_width: Number, canvas_height: Number, dtheta: Number) -> CubeResult<()> {
+        let width = number(&canvas_width, "canvas_width")?;
+        let height = number(&canvas_height, "canvas_height")?;
+        let frame = Frame::new(&self.camera, width, height, number(&dtheta, "dtheta")? as f32);
+
+        renderer::clear(&self.gl);
+        self.draw(&frame, &glm::TMat4::identity())?;
+        self.gl.flush();
+
+        Ok(())
+    }
+
+    /// RGBA pixels of the frame at `dtheta`, rendered offscreen at `width` x `height`.
+    pub fn capture(&self, width: u32, height: u32, dtheta: f32) -> CubeResult<Vec<u8>> {
+        renderer::read_frame(&self.gl, &self.camera, width, height, dtheta, |frame| {
+            self.draw(frame, &glm::TMat4::identity())
+        })
+    }
+
+    /// Builds a cube drawing into an existing context, e.g. one owned by a
+    /// [`Renderer`](crate::renderer::Renderer).
+    pub fn try_with_context(gl: GL) -> CubeResult<Self> {
+        let cube_shader = shader::Shader::uber(&gl, shader::Features::default())?;
+        let (positions_buffer, position_attr) = Self::init_vertices(&gl, &cube_shader)?;
+
+        Ok(Self { camera: Camera::new(CAMERA_DISTANCE), gl, cube_shader, positions_buffer, position_attr })
+    }
+
+    fn init_vertices(gl: &GL, cube_shader: &shader::Shader) -> CubeResult<(Buffer, u32)> {
+        let positions_buffer = gl.create_buffer().ok_or(Error::Allocation("positions vbo"))?;
+
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&positions_buffer));
+
+        let positions_data = buf_f32!(&geometry::VERTICES);
+
+        gl.buffer_data_with_opt_array_buffer(
+            GL::ARRAY_BUFFER, Some(&positions_data), GL::STATIC_DRAW
+        );
+
+        let position_attr = cube_shader.attribute_location("position", GL::FLOAT_VEC3)?;
+
+        Ok((positions_buffer, position_attr))
+    }
+}
+
+impl Drawable for Cube {
+    fn draw(&self, frame: &Frame, model: &glm::TMat4<f32>) -> CubeResult<()> {
+        self.cube_shader.use_shader(&self.gl);
+        self.cube_shader.set_uniform(&self.gl, "v", &frame.view)?;
+        self.cube_shader.set_uniform(&self.gl, "p", &frame.projection)?;
+
+        self.gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.positions_buffer));
+        self.gl.vertex_attrib_pointer_with_i32(self.position_attr, 3, GL::FLOAT, false, 0, 0);
+        self.gl.enable_vertex_attrib_array(self.position_attr);
+
+        for (color, local) in nested_cubes(frame.dtheta) {
+            self.cube_shader.set_uniform(&self.gl, "baseColor", &glm::Vec3::from(color))?;
+            self.cube_shader.set_uniform(&self.gl, "m", &(model * local))?;
+            self.gl.draw_arrays(GL::LINES, 0, 24);
+        }
+
+        self.gl.disable_vertex_attrib_array(self.position_attr);
+
+        Ok(())
+    }
+
+    /// The outer cube tumbles about its centre, sweeping out the sphere through its corners.
+    fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds::cube(0.75f32.sqrt()))
+    }
+}
+
+#[cfg(feature = "wasm")]
+crate::camera_controls!(Cube, camera, bounds);
+#[cfg(feature = "wasm")]
+crate::frame_captures!(Cube, capture);
@@ -0,0 +1,179 @@
+use super::parser::{Expr, ParseError, Variables};
+use crate::parametric_surfaces::ParametricSurface;
+use nalgebra_glm as glm;
+use std::fmt;
+
+/// Number of samples along the opposite parameter used to detect periodicity.
+const PERIODICITY_SAMPLES: usize = 16;
+const PERIODICITY_EPSILON: f32 = 1e-4;
+
+/// Surface whose coordinates are given by runtime expressions in u, v and t.
+pub struct ExpressionGeometry {
+    x: Expr,
+    y: Expr,
+    z: Expr,
+    u_domain: (f32, f32),
+    v_domain: (f32, f32),
+    periodic_u: bool,
+    periodic_v: bool,
+    t: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExpressionError {
+    /// Parse error tagged with the coordinate expression it came from.
+    Parse { axis: char, error: ParseError },
+    /// The `parameter` domain is empty, reversed or not finite.
+    Domain { parameter: char, domain: (f32, f32) },
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { axis, error } => write!(f, "{}(u, v), {}", axis, error),
+            Self::Domain { parameter, domain: (start, end) } => write!(
+                f, "the {} domain [{}, {}] must be finite with its start below its end", parameter, start, end
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExpressionError {}
+
+impl ExpressionGeometry {
+    /// Parses the three coordinate expressions, with `t` at 0. Whether the surface wraps around in u
+    /// or v is detected by comparing both edges of the domain, so closed surfaces get seamless meshes.
+    pub fn new(
+        x: &str, y: &str, z: &str, u_domain: (f32, f32), v_domain: (f32, f32)
+    ) -> Result<Self, ExpressionError> {
+        for (parameter, domain) in [('u', u_domain), ('v', v_domain)] {
+            let (start, end) = domain;
+            if !(start.is_finite() && end.is_finite() && start < end) {
+                return Err(ExpressionError::Domain { parameter, domain });
+            }
+        }
+        let parse = |axis: char, src: &str| Expr::parse(src).map_err(|error| ExpressionError::Parse { axis, error });
+
+        let mut geometry = Self {
+            x: parse('x', x)?,
+            y: parse('y', y)?,
+            z: parse('z', z)?,
+            u_domain,
+            v_domain,
+            periodic_u: false,
+            periodic_v: false,
+            t: 0.0,
+        };
+        geometry.detect_periodicity();
+
+        Ok(geometry)
+    }
+
+    pub fn time(&self) -> f32 {
+        self.t
+    }
+
+    /// Sets the value of `t` in the expressions. Periodicity is detected again since a seam may only
+    /// close at some values of `t`.
+    pub fn set_time(&mut self, t: f32) {
+        self.t = t;
+        self.detect_periodicity();
+    }
+
+    fn detect_periodicity(&mut self) {
+        let (u_start, u_end) = self.u_domain;
+        let (v_start, v_end) = self.v_domain;
+        self.periodic_u = self.edges_match(|s| {
+            let v = v_start + (v_end - v_start) * s;
+            ((u_start, v), (u_end, v))
+        });
+        self.periodic_v = self.edges_match(|s| {
+            let u = u_start + (u_end - u_start) * s;
+            ((u, v_start), (u, v_end))
+        });
+    }
+
+    /// Whether the pairs of parameters returned by `edge` for s in [0, 1] map to the same points.
+    fn edges_match<F>(&self, edge: F) -> bool
+    where
+        F: Fn(f32) -> ((f32, f32), (f32, f32)),
+    {
+        (0..=PERIODICITY_SAMPLES).all(|i| {
+            let ((u0, v0), (u1, v1)) = edge(i as f32 / PERIODICITY_SAMPLES as f32);
+            let (a, b) = (self.position(u0, v0), self.position(u1, v1));
+            let scale = glm::length(&a).max(1.0);
+            glm::distance(&a, &b) <= PERIODICITY_EPSILON * scale
+        })
+    }
+}
+
+impl ParametricSurface for ExpressionGeometry {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let vars = Variables { u, v, t: self.t };
+        glm::vec3(self.x.eval(&vars), self.y.eval(&vars), self.z.eval(&vars))
+    }
+
+    fn u_domain(&self) -> (f32, f32) {
+        self.u_domain
+    }
+
+    fn v_domain(&self) -> (f32, f32) {
+        self.v_domain
+    }
+
+    fn periodic_u(&self) -> bool {
+        self.periodic_u
+    }
+
+    fn periodic_v(&self) -> bool {
+        self.periodic_v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    #[test]
+    fn detects_closed_edges() {
+        let torus = ExpressionGeometry::new(
+            "(1 + 0.3*cos(v))*cos(u)", "(1 + 0.3*cos(v))*sin(u)", "0.3*sin(v)", (0.0, TAU), (0.0, TAU)
+        ).unwrap();
+        assert!(torus.periodic_u() && torus.periodic_v());
+
+        let cylinder = ExpressionGeometry::new("cos(u)", "sin(u)", "v", (0.0, TAU), (0.0, 1.0)).unwrap();
+        assert!(cylinder.periodic_u() && !cylinder.periodic_v());
+    }
+
+    #[test]
+    fn setting_the_time_redetects_periodicity() {
+        // A cylinder at t = 0 that unrolls into a spiral sheet as t grows.
+        let mut geometry = ExpressionGeometry::new("cos(u) + t*u", "sin(u)", "v", (0.0, TAU), (0.0, 1.0)).unwrap();
+        assert!(geometry.periodic_u());
+
+        geometry.set_time(1.0);
+        assert_eq!(geometry.time(), 1.0);
+        assert!(!geometry.periodic_u());
+
+        geometry.set_time(0.0);
+        assert!(geometry.periodic_u());
+    }
+
+    #[test]
+    fn errors_name_their_axis() {
+        let error = ExpressionGeometry::new("u", "v", "sin(", (0.0, 1.0), (0.0, 1.0)).err().unwrap();
+        assert!(matches!(error, ExpressionError::Parse { axis: 'z', .. }));
+        assert_eq!(error.to_string(), "z(u, v), column 5: unexpected end of input");
+    }
+
+    #[test]
+    fn domains_must_be_finite_and_increasing() {
+        let domain_error = |u_domain, v_domain| ExpressionGeometry::new("u", "v", "0", u_domain, v_domain).err();
+        assert_eq!(domain_error((1.0, 1.0), (0.0, 1.0)), Some(ExpressionError::Domain { parameter: 'u', domain: (1.0, 1.0) }));
+        assert_eq!(domain_error((0.0, 1.0), (1.0, 0.0)), Some(ExpressionError::Domain { parameter: 'v', domain: (1.0, 0.0) }));
+        assert!(domain_error((0.0, f32::INFINITY), (0.0, 1.0)).is_some());
+        assert!(domain_error((0.0, 1.0), (f32::NAN, 1.0)).is_some());
+        assert_eq!(domain_error((-1.0, 1.0), (0.0, 1.0)), None);
+    }
+}
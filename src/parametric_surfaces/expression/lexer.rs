@@ -0,0 +1,96 @@
+use super::parser::ParseError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    Number(f32),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LParen,
+    RParen,
+    Comma,
+    End,
+}
+
+/// A token along with the 1-based column it starts at.
+#[derive(Clone, Debug)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub column: usize,
+}
+
+pub fn tokenize(src: &str) -> Result<Vec<Token>, ParseError> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+
+            // Exponent, e.g. 1e-3.
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+
+            let literal: String = chars[start..i].iter().collect();
+            let value = literal.parse::<f32>()
+                .map_err(|_| ParseError::new(column, format!("invalid number '{}'", literal)))?;
+
+            tokens.push(Token { kind: TokenKind::Number(value), column });
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let ident = chars[start..i].iter().collect();
+            tokens.push(Token { kind: TokenKind::Ident(ident), column });
+            continue;
+        }
+
+        let kind = match c {
+            '+' => TokenKind::Plus,
+            '-' => TokenKind::Minus,
+            '*' => TokenKind::Star,
+            '/' => TokenKind::Slash,
+            '^' => TokenKind::Caret,
+            '(' => TokenKind::LParen,
+            ')' => TokenKind::RParen,
+            ',' => TokenKind::Comma,
+            _ => return Err(ParseError::new(column, format!("unexpected character '{}'", c))),
+        };
+
+        tokens.push(Token { kind, column });
+        i += 1;
+    }
+
+    tokens.push(Token { kind: TokenKind::End, column: chars.len() + 1 });
+
+    Ok(tokens)
+}
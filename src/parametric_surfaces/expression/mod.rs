@@ -0,0 +1,10 @@
+mod geometry;
+mod lexer;
+mod parser;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use geometry::{ExpressionError, ExpressionGeometry};
+pub use parser::{Expr, ParseError, Variables};
+#[cfg(feature = "wasm")]
+pub use wasm::ExpressionSurface;
@@ -0,0 +1,422 @@
+use super::lexer::{self, Token, TokenKind};
+use std::f32::consts::{E, PI, TAU};
+use std::fmt;
+
+/// Parse failure pointing at the 1-based column of the offending token.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(column: usize, message: String) -> Self {
+        Self { column, message }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Variable {
+    U,
+    V,
+    T,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Ln,
+    Log10,
+    Sqrt,
+    Abs,
+    Sign,
+    Floor,
+    Ceil,
+    Min,
+    Max,
+    Pow,
+}
+
+/// Parsed expression tree. Constants are folded into `Number` and identifiers are resolved while
+/// parsing, so evaluation can't fail.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Number(f32),
+    Variable(Variable),
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+/// Values bound to the free variables of an expression.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Variables {
+    pub u: f32,
+    pub v: f32,
+    pub t: f32,
+}
+
+/// Deepest nesting of parentheses, calls and unary operators the parser recurses into, so hostile
+/// input fails with an error instead of overflowing the stack.
+const MAX_DEPTH: usize = 256;
+
+const CONSTANTS: [(&str, f32); 4] = [("pi", PI), ("tau", TAU), ("e", E), ("phi", 1.618_034)];
+
+impl Function {
+    fn lookup(name: &str) -> Option<Self> {
+        let function = match name {
+            "sin" => Self::Sin,
+            "cos" => Self::Cos,
+            "tan" => Self::Tan,
+            "asin" => Self::Asin,
+            "acos" => Self::Acos,
+            "atan" => Self::Atan,
+            "atan2" => Self::Atan2,
+            "sinh" => Self::Sinh,
+            "cosh" => Self::Cosh,
+            "tanh" => Self::Tanh,
+            "exp" => Self::Exp,
+            "ln" => Self::Ln,
+            "log" => Self::Log10,
+            "sqrt" => Self::Sqrt,
+            "abs" => Self::Abs,
+            "sign" => Self::Sign,
+            "floor" => Self::Floor,
+            "ceil" => Self::Ceil,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "pow" => Self::Pow,
+            _ => return None,
+        };
+        Some(function)
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Self::Atan2 | Self::Min | Self::Max | Self::Pow => 2,
+            _ => 1,
+        }
+    }
+
+    fn apply(self, args: &[f32]) -> f32 {
+        let x = args[0];
+        match self {
+            Self::Sin => x.sin(),
+            Self::Cos => x.cos(),
+            Self::Tan => x.tan(),
+            Self::Asin => x.asin(),
+            Self::Acos => x.acos(),
+            Self::Atan => x.atan(),
+            Self::Atan2 => x.atan2(args[1]),
+            Self::Sinh => x.sinh(),
+            Self::Cosh => x.cosh(),
+            Self::Tanh => x.tanh(),
+            Self::Exp => x.exp(),
+            Self::Ln => x.ln(),
+            Self::Log10 => x.log10(),
+            Self::Sqrt => x.sqrt(),
+            Self::Abs => x.abs(),
+            Self::Sign => if x == 0.0 { 0.0 } else { x.signum() },
+            Self::Floor => x.floor(),
+            Self::Ceil => x.ceil(),
+            Self::Min => x.min(args[1]),
+            Self::Max => x.max(args[1]),
+            Self::Pow => x.powf(args[1]),
+        }
+    }
+}
+
+impl Expr {
+    /// Parses an infix expression over the variables `u`, `v` and `t`.
+    ///
+    /// Supports `+ - * / ^` (with `^` binding tightest and associating to the right), unary minus,
+    /// parentheses, the constants `pi`, `tau`, `e` and `phi`, and the functions `sin`, `cos`,
+    /// `tan`, `asin`, `acos`, `atan`, `atan2`, `sinh`, `cosh`, `tanh`, `exp`, `ln`, `log`, `sqrt`,
+    /// `abs`, `sign`, `floor`, `ceil`, `min`, `max` and `pow`.
+    pub fn parse(src: &str) -> Result<Self, ParseError> {
+        let tokens = lexer::tokenize(src)?;
+        let mut parser = Parser { tokens, pos: 0, depth: 0 };
+        let expr = parser.expression()?;
+
+        match parser.peek() {
+            Token { kind: TokenKind::End, .. } => Ok(expr),
+            token => Err(ParseError::new(token.column, "expected an operator or end of input".to_string())),
+        }
+    }
+
+    pub fn eval(&self, vars: &Variables) -> f32 {
+        match self {
+            Self::Number(n) => *n,
+            Self::Variable(Variable::U) => vars.u,
+            Self::Variable(Variable::V) => vars.v,
+            Self::Variable(Variable::T) => vars.t,
+            Self::Neg(expr) => -expr.eval(vars),
+            Self::Binary(op, lhs, rhs) => {
+                let (a, b) = (lhs.eval(vars), rhs.eval(vars));
+                match op {
+                    BinaryOp::Add => a + b,
+                    BinaryOp::Sub => a - b,
+                    BinaryOp::Mul => a * b,
+                    BinaryOp::Div => a / b,
+                    BinaryOp::Pow => a.powf(b),
+                }
+            }
+            Self::Call(function, args) => {
+                let args: Vec<f32> = args.iter().map(|arg| arg.eval(vars)).collect();
+                function.apply(&args)
+            }
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::End {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, kind: TokenKind, what: &str) -> Result<Token, ParseError> {
+        let token = self.advance();
+        if token.kind == kind {
+            Ok(token)
+        } else {
+            Err(ParseError::new(token.column, format!("expected {}", what)))
+        }
+    }
+
+    // expression := term (("+" | "-") term)*
+    fn expression(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.term()?;
+
+        loop {
+            let op = match self.peek().kind {
+                TokenKind::Plus => BinaryOp::Add,
+                TokenKind::Minus => BinaryOp::Sub,
+                _ => return Ok(lhs),
+            };
+            self.advance();
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+    }
+
+    // term := unary (("*" | "/") unary)*
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.unary()?;
+
+        loop {
+            let op = match self.peek().kind {
+                TokenKind::Star => BinaryOp::Mul,
+                TokenKind::Slash => BinaryOp::Div,
+                _ => return Ok(lhs),
+            };
+            self.advance();
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    // unary := ("-" | "+") unary | power
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        // Every recursive rule passes through here, so this is where nesting is counted.
+        if self.depth == MAX_DEPTH {
+            let column = self.peek().column;
+            return Err(ParseError::new(column, format!("nested more than {} levels deep", MAX_DEPTH)));
+        }
+        self.depth += 1;
+
+        let expr = match self.peek().kind {
+            TokenKind::Minus => {
+                self.advance();
+                self.unary().map(|expr| Expr::Neg(Box::new(expr)))
+            }
+            TokenKind::Plus => {
+                self.advance();
+                self.unary()
+            }
+            _ => self.power(),
+        };
+
+        self.depth -= 1;
+        expr
+    }
+
+    // power := primary ("^" unary)?
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base = self.primary()?;
+
+        if self.peek().kind == TokenKind::Caret {
+            self.advance();
+            let exponent = self.unary()?;
+            return Ok(Expr::Binary(BinaryOp::Pow, Box::new(base), Box::new(exponent)));
+        }
+
+        Ok(base)
+    }
+
+    // primary := number | variable | constant | function "(" args ")" | "(" expression ")"
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let token = self.advance();
+
+        match token.kind {
+            TokenKind::Number(n) => Ok(Expr::Number(n)),
+            TokenKind::LParen => {
+                let expr = self.expression()?;
+                self.expect(TokenKind::RParen, "')'")?;
+                Ok(expr)
+            }
+            TokenKind::Ident(name) => self.identifier(&name, token.column),
+            TokenKind::End => Err(ParseError::new(token.column, "unexpected end of input".to_string())),
+            _ => Err(ParseError::new(token.column, "expected a number, variable or '('".to_string())),
+        }
+    }
+
+    fn identifier(&mut self, name: &str, column: usize) -> Result<Expr, ParseError> {
+        match name {
+            "u" => return Ok(Expr::Variable(Variable::U)),
+            "v" => return Ok(Expr::Variable(Variable::V)),
+            "t" => return Ok(Expr::Variable(Variable::T)),
+            _ => (),
+        }
+
+        if let Some(&(_, value)) = CONSTANTS.iter().find(|(constant, _)| *constant == name) {
+            return Ok(Expr::Number(value));
+        }
+
+        let function = Function::lookup(name)
+            .ok_or_else(|| ParseError::new(column, format!("unknown identifier '{}'", name)))?;
+
+        self.expect(TokenKind::LParen, &format!("'(' after '{}'", name))?;
+
+        let mut args = vec![self.expression()?];
+        while self.peek().kind == TokenKind::Comma {
+            self.advance();
+            args.push(self.expression()?);
+        }
+
+        self.expect(TokenKind::RParen, "')'")?;
+
+        if args.len() != function.arity() {
+            return Err(ParseError::new(
+                column,
+                format!("'{}' expects {} argument(s), got {}", name, function.arity(), args.len()),
+            ));
+        }
+
+        Ok(Expr::Call(function, args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(src: &str) -> f32 {
+        Expr::parse(src).unwrap().eval(&Variables { u: 2.0, v: 3.0, t: 0.5 })
+    }
+
+    fn error(src: &str) -> ParseError {
+        Expr::parse(src).unwrap_err()
+    }
+
+    #[test]
+    fn binary_operators_follow_precedence() {
+        assert_eq!(eval("1 + 2 * 3"), 7.0);
+        assert_eq!(eval("(1 + 2) * 3"), 9.0);
+        assert_eq!(eval("8 - 4 - 2"), 2.0);
+        assert_eq!(eval("8 / 4 / 2"), 1.0);
+        assert_eq!(eval("2 * 3 ^ 2"), 18.0);
+        // `^` associates to the right.
+        assert_eq!(eval("2 ^ 3 ^ 2"), 512.0);
+    }
+
+    #[test]
+    fn unary_minus_binds_looser_than_powers() {
+        assert_eq!(eval("-2 ^ 2"), -4.0);
+        assert_eq!(eval("(-2) ^ 2"), 4.0);
+        assert_eq!(eval("2 ^ -1"), 0.5);
+        assert_eq!(eval("2 * -3"), -6.0);
+        assert_eq!(eval("--u"), 2.0);
+        assert_eq!(eval("+u - -v"), 5.0);
+    }
+
+    #[test]
+    fn variables_constants_and_functions_evaluate() {
+        assert_eq!(eval("u * v + t"), 6.5);
+        assert_eq!(eval("1e-1 * 20"), 2.0);
+        assert_eq!(eval("cos(pi)"), -1.0);
+        assert_eq!(eval("max(u, v) - min(u, v)"), 1.0);
+        assert_eq!(eval("pow(u, v)"), 8.0);
+        assert_eq!(eval("sign(0) + sign(-v)"), -1.0);
+        assert_eq!(Expr::parse("2 * pi").unwrap(), Expr::Binary(
+            BinaryOp::Mul, Box::new(Expr::Number(2.0)), Box::new(Expr::Number(PI))
+        ));
+    }
+
+    #[test]
+    fn functions_check_their_arity() {
+        assert_eq!(error("1 + min(u)"), ParseError::new(5, "'min' expects 2 argument(s), got 1".to_string()));
+        assert_eq!(error("sin(u, v)"), ParseError::new(1, "'sin' expects 1 argument(s), got 2".to_string()));
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_column() {
+        assert_eq!(error("u + foo").column, 5);
+        assert_eq!(error("u $ v"), ParseError::new(3, "unexpected character '$'".to_string()));
+        assert_eq!(error("(u + v"), ParseError::new(7, "expected ')'".to_string()));
+        assert_eq!(error("u v"), ParseError::new(3, "expected an operator or end of input".to_string()));
+        assert_eq!(error("u *"), ParseError::new(4, "unexpected end of input".to_string()));
+        assert_eq!(error("sin u"), ParseError::new(5, "expected '(' after 'sin'".to_string()));
+        assert_eq!(error("1..2"), ParseError::new(1, "invalid number '1..2'".to_string()));
+        assert_eq!(error("u + )").column, 5);
+    }
+
+    #[test]
+    fn nesting_is_capped() {
+        let nested = |depth: usize| format!("{}u{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(eval(&nested(MAX_DEPTH - 1)), 2.0);
+        // The top level counts as one, so the error points at whatever comes after the last '('.
+        let message = "nested more than 256 levels deep".to_string();
+        assert_eq!(error(&nested(MAX_DEPTH)), ParseError::new(MAX_DEPTH + 1, message));
+        assert_eq!(error(&"-".repeat(100_000)).column, MAX_DEPTH + 1);
+        assert!(Expr::parse(&format!("{}u{}", "sin(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH))).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use super::ExpressionGeometry;
+use crate::error::Error;
+use crate::{camera_controls, frame_captures, lighting_controls, material_controls, mesh_exports, number_js, shader_editing};
+use crate::parametric_surfaces::renderer::SurfaceRenderer;
+use crate::parametric_surfaces::ParametricSurface;
+use js_sys::{JsString, Number};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Surface defined by formulas typed in at runtime, e.g. `x = (1 + 0.3*cos(v))*cos(u)`.
+#[wasm_bindgen]
+pub struct ExpressionSurface {
+    geometry: ExpressionGeometry,
+    renderer: SurfaceRenderer,
+}
+
+type ExpressionResult<T> = Result<T, Error>;
+
+#[wasm_bindgen]
+impl ExpressionSurface {
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        canvas_id: JsString,
+        x: JsString, y: JsString, z: JsString,
+        u_min: Number, u_max: Number,
+        v_min: Number, v_max: Number,
+    ) -> ExpressionResult<ExpressionSurface> {
+        let u_domain = (number_js!(u_min)? as f32, number_js!(u_max)? as f32);
+        let v_domain = (number_js!(v_min)? as f32, number_js!(v_max)? as f32);
+        let geometry = ExpressionGeometry::new(
+            &String::from(x), &String::from(y), &String::from(z), u_domain, v_domain
+        )?;
+        let renderer = SurfaceRenderer::try_new(canvas_id, &geometry)?;
+
+        Ok(Self { geometry, renderer })
+    }
+
+    #[wasm_bindgen]
+    pub fn render(&self, canvas_width: Number, canvas_height: Number, dtheta: Number) -> ExpressionResult<()> {
+        self.renderer.try_render(canvas_width, canvas_height, dtheta)
+    }
+
+    /// Sets the value of `t` in the expressions and regenerates the mesh.
+    #[wasm_bindgen]
+    pub fn set_time(&mut self, t: Number) -> ExpressionResult<()> {
+        self.geometry.set_time(number_js!(t)? as f32);
+        self.renderer.try_set_surface(&self.geometry)
+    }
+}
+
+impl ExpressionSurface {
+    fn surface(&self) -> &dyn ParametricSurface {
+        &self.geometry
+    }
+}
+
+camera_controls!(ExpressionSurface, renderer.camera, renderer.bounds);
+mesh_exports!(ExpressionSurface, renderer);
+shader_editing!(ExpressionSurface, renderer);
+lighting_controls!(ExpressionSurface, renderer.lighting);
+material_controls!(ExpressionSurface, renderer.material);
+frame_captures!(ExpressionSurface, renderer.capture);
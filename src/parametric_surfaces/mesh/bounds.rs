@@ -0,0 +1,55 @@
+use nalgebra_glm as glm;
+
+/// Axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub min: glm::Vec3,
+    pub max: glm::Vec3,
+}
+
+impl Bounds {
+    /// Smallest box containing every point of a flat `[x, y, z, x, y, z, ...]` slice, or `None`
+    /// when there are no points.
+    pub fn from_positions(positions: &[f32]) -> Option<Self> {
+        let mut points = positions.chunks_exact(3).map(|p| glm::vec3(p[0], p[1], p[2]));
+        let first = points.next()?;
+
+        Some(points.fold(Self::point(first), |bounds, point| bounds.union(&Self::point(point))))
+    }
+
+    fn point(point: glm::Vec3) -> Self {
+        Self { min: point, max: point }
+    }
+
+    /// Cube centred on the origin reaching `half_extent` along each axis.
+    pub fn cube(half_extent: f32) -> Self {
+        let corner = glm::vec3(half_extent, half_extent, half_extent);
+        Self { min: -corner, max: corner }
+    }
+
+    pub fn center(&self) -> glm::Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Radius of the sphere through the box's corners.
+    pub fn radius(&self) -> f32 {
+        glm::distance(&self.min, &self.max) * 0.5
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: glm::min2(&self.min, &other.min),
+            max: glm::max2(&self.max, &other.max),
+        }
+    }
+
+    /// Box containing this one after it has been moved by `matrix`.
+    pub fn transform(&self, matrix: &glm::TMat4<f32>) -> Self {
+        let corner = |i: usize| {
+            let pick = |axis: usize| if i & (1 << axis) == 0 { self.min[axis] } else { self.max[axis] };
+            Self::point((matrix * glm::vec4(pick(0), pick(1), pick(2), 1.0)).xyz())
+        };
+
+        (1..8).map(corner).fold(corner(0), |bounds, point| bounds.union(&point))
+    }
+}
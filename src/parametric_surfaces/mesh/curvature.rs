@@ -0,0 +1,103 @@
+use super::{compute_normal, numeric_partials};
+use crate::parametric_surfaces::ParametricSurface;
+use nalgebra_glm as glm;
+
+/// Step for second differences, as a fraction of the domain's span. Second differences lose
+/// precision as h² shrinks, so this is coarser than the step used for normals.
+const SECOND_DIFF_STEP: f32 = 5e-3;
+
+/// Determinant of the first fundamental form below which the parametrisation is degenerate.
+const DEGENERATE_EPSILON: f32 = 1e-10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Curvature {
+    pub gaussian: f32,
+    /// Signed with respect to the normal from [`compute_normal`].
+    pub mean: f32,
+}
+
+/// Gaussian and mean curvature at (u, v) from the first and second fundamental forms.
+///
+/// Where the parametrisation is degenerate (sphere poles, cone tips) a sample nudged towards the
+/// interior of the domain is used instead, and if that is degenerate too both curvatures are 0.
+pub fn curvature(surface: &dyn ParametricSurface, u: f32, v: f32) -> Curvature {
+    let nudge = |x: f32, (start, end): (f32, f32)| {
+        let step = (end - start) * SECOND_DIFF_STEP;
+        if x + step <= end { x + step } else { x - step }
+    };
+
+    try_curvature(surface, u, v)
+        .or_else(|| try_curvature(surface, nudge(u, surface.u_domain()), nudge(v, surface.v_domain())))
+        .unwrap_or_default()
+}
+
+fn try_curvature(surface: &dyn ParametricSurface, u: f32, v: f32) -> Option<Curvature> {
+    let (xu, xv) = surface.partials(u, v).unwrap_or_else(|| numeric_partials(surface, u, v));
+    let (xuu, xuv, xvv) = second_partials(surface, u, v);
+    let n = compute_normal(surface, u, v);
+
+    let (e, f, g) = (glm::dot(&xu, &xu), glm::dot(&xu, &xv), glm::dot(&xv, &xv));
+    let (l, m, nn) = (glm::dot(&xuu, &n), glm::dot(&xuv, &n), glm::dot(&xvv, &n));
+    let det = e * g - f * f;
+
+    if !det.is_finite() || det < DEGENERATE_EPSILON {
+        return None;
+    }
+
+    Some(Curvature {
+        gaussian: (l * nn - m * m) / det,
+        mean: (e * nn - 2.0 * f * m + g * l) / (2.0 * det),
+    })
+}
+
+/// Second differences of the position function. Like [`numeric_partials`], samples never leave
+/// the domain: near an edge the stencil is moved inwards instead.
+fn second_partials(surface: &dyn ParametricSurface, u: f32, v: f32) -> (glm::Vec3, glm::Vec3, glm::Vec3) {
+    let (u_start, u_end) = surface.u_domain();
+    let (v_start, v_end) = surface.v_domain();
+    let hu = (u_end - u_start) * SECOND_DIFF_STEP;
+    let hv = (v_end - v_start) * SECOND_DIFF_STEP;
+    let u = u.clamp(u_start + hu, u_end - hu);
+    let v = v.clamp(v_start + hv, v_end - hv);
+    let p = |du: f32, dv: f32| surface.position(u + du * hu, v + dv * hv);
+
+    let xuu = (p(1.0, 0.0) - p(0.0, 0.0) * 2.0 + p(-1.0, 0.0)) / (hu * hu);
+    let xvv = (p(0.0, 1.0) - p(0.0, 0.0) * 2.0 + p(0.0, -1.0)) / (hv * hv);
+    let xuv = (p(1.0, 1.0) - p(1.0, -1.0) - p(-1.0, 1.0) + p(-1.0, -1.0)) / (4.0 * hu * hv);
+
+    (xuu, xuv, xvv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parametric_surfaces::catalog::{Helicoid, Sphere};
+
+    #[test]
+    fn sphere_has_constant_curvature() {
+        let sphere = Sphere { r: 2.0 };
+        for (u, v) in [(0.3, 0.7), (2.0, 1.5), (5.0, 2.5)] {
+            let c = curvature(&sphere, u, v);
+            assert!((c.gaussian - 0.25).abs() < 5e-3, "K = {} at ({u}, {v})", c.gaussian);
+            assert!((c.mean.abs() - 0.5).abs() < 5e-3, "H = {} at ({u}, {v})", c.mean);
+        }
+    }
+
+    #[test]
+    fn helicoid_is_minimal() {
+        let helicoid = Helicoid::default();
+        let pitch = helicoid.height / (2.0 * std::f32::consts::PI * helicoid.turns);
+        let u = 0.4;
+        let expected = -(pitch * pitch) / (pitch * pitch + u * u).powi(2);
+
+        let c = curvature(&helicoid, u, 0.5);
+        assert!((c.gaussian / expected - 1.0).abs() < 0.05, "K = {}, expected {}", c.gaussian, expected);
+        assert!(c.mean.abs() < 0.05 * expected.abs().sqrt(), "H = {}", c.mean);
+    }
+
+    #[test]
+    fn poles_fall_back_to_a_nearby_sample() {
+        let c = curvature(&Sphere { r: 1.0 }, 0.0, 0.0);
+        assert!((c.gaussian - 1.0).abs() < 5e-2, "K = {}", c.gaussian);
+    }
+}
@@ -0,0 +1,86 @@
+/// Fewest segments a periodic domain is split into. With fewer, the samples around it collapse into
+/// a line or a point.
+const MIN_PERIODIC_SEGMENTS: usize = 3;
+
+/// Evenly spaced samples of one parameter's domain, addressed by integer index.
+///
+/// Every sample is computed directly from its index rather than by repeatedly adding a step, so no
+/// rounding error accumulates and the last sample of an open domain is exactly its end.
+#[derive(Clone, Copy, Debug)]
+pub struct Grid {
+    start: f32,
+    end: f32,
+    segments: usize,
+    periodic: bool,
+}
+
+impl Grid {
+    /// Splits `domain` into `segments` equal intervals. At least one segment is always used, and
+    /// at least three for a periodic domain.
+    pub fn new(domain: (f32, f32), segments: usize, periodic: bool) -> Self {
+        let (start, end) = domain;
+        let min_segments = if periodic { MIN_PERIODIC_SEGMENTS } else { 1 };
+        Self { start, end, segments: segments.max(min_segments), periodic }
+    }
+
+    pub fn periodic(&self) -> bool {
+        self.periodic
+    }
+
+    pub fn segments(&self) -> usize {
+        self.segments
+    }
+
+    /// Number of distinct samples: `segments` for a periodic domain, where the end coincides with
+    /// the start and is left out, otherwise `segments + 1`.
+    pub fn samples(&self) -> usize {
+        if self.periodic { self.segments } else { self.segments + 1 }
+    }
+
+    /// Parameter value of sample `i`, for `i` in `0..=segments`.
+    pub fn at(&self, i: usize) -> f32 {
+        if i == self.segments {
+            self.end
+        } else {
+            self.start + (self.end - self.start) * self.fraction(i)
+        }
+    }
+
+    /// Position of sample `i` within the domain, from 0 at the start to 1 at the end.
+    pub fn fraction(&self, i: usize) -> f32 {
+        i as f32 / self.segments as f32
+    }
+
+    /// Index of the sample that vertex `i` lands on, wrapping periodic domains back to 0.
+    pub fn wrap(&self, i: usize) -> usize {
+        i % self.samples()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, f32)> + '_ {
+        (0..self.samples()).map(move |i| (i, self.at(i)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn periodic_domains_keep_three_segments() {
+        assert_eq!(Grid::new((0.0, 1.0), 1, true).segments(), 3);
+        assert_eq!(Grid::new((0.0, 1.0), 0, false).segments(), 1);
+        assert_eq!(Grid::new((0.0, 1.0), 1, false).samples(), 2);
+    }
+
+    #[test]
+    fn samples_are_exact_at_both_ends() {
+        let grid = Grid::new((-1.0, 2.0), 7, false);
+        assert_eq!(grid.at(0), -1.0);
+        assert_eq!(grid.at(7), 2.0);
+        assert_eq!(grid.iter().count(), 8);
+
+        let periodic = Grid::new((0.0, 1.0), 4, true);
+        assert_eq!(periodic.iter().count(), 4);
+        assert_eq!(periodic.wrap(4), 0);
+    }
+}
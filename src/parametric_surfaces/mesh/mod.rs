@@ -0,0 +1,87 @@
+mod bounds;
+mod curvature;
+mod grid;
+mod normals;
+mod solidify;
+mod tessellation;
+mod topology;
+
+pub use bounds::Bounds;
+pub use curvature::{curvature, Curvature};
+pub use grid::Grid;
+pub use normals::{compute_normal, numeric_partials};
+pub use solidify::solidify;
+pub use tessellation::tessellate;
+pub use topology::{Edge, TopologyError, Welded};
+
+/// Indexed triangle mesh. Vertex attributes are stored flat so they can be handed straight to
+/// `buf_f32!`; every three entries of `indices` form one counter-clockwise triangle.
+#[derive(Default)]
+pub struct Mesh {
+    pub positions: Vec<f32>,
+    pub normals: Vec<f32>,
+    pub colors: Vec<f32>,
+    pub tex_coords: Vec<f32>,
+    /// The (u, v) each vertex was sampled at.
+    pub parameters: Vec<f32>,
+    pub indices: Indices,
+    /// Whether the surface wraps around in u and in v, so triangles along the last column/row index
+    /// back into the first.
+    pub periodic: (bool, bool),
+}
+
+/// Index buffer, narrowed to `u16` whenever the vertex count allows it since WebGL 1 needs the
+/// `OES_element_index_uint` extension to draw with `u32` indices.
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Mesh {
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len() / 3
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_positions(&self.positions)
+    }
+}
+
+impl Default for Indices {
+    fn default() -> Self {
+        Self::U16(vec![])
+    }
+}
+
+impl Indices {
+    /// Picks the narrowest index type able to address `vertex_count` vertices.
+    pub fn new(indices: Vec<u32>, vertex_count: usize) -> Self {
+        if vertex_count <= u16::MAX as usize + 1 {
+            Self::U16(indices.into_iter().map(|i| i as u16).collect())
+        } else {
+            Self::U32(indices)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::U16(indices) => indices.len(),
+            Self::U32(indices) => indices.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> Box<dyn Iterator<Item = u32> + '_> {
+        match self {
+            Self::U16(indices) => Box::new(indices.iter().map(|&i| i as u32)),
+            Self::U32(indices) => Box::new(indices.iter().copied()),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::parametric_surfaces::ParametricSurface;
+use nalgebra_glm as glm;
+
+/// Step for central differences, as a fraction of the domain's span.
+const DIFF_STEP: f32 = 1e-3;
+
+/// Squared length below which a cross product is treated as degenerate.
+const DEGENERATE_EPSILON: f32 = 1e-12;
+
+/// Unit normal at (u, v), oriented along ∂p/∂u × ∂p/∂v.
+///
+/// Where the surface pinches to a point (sphere poles, cone tips) the partial derivatives are
+/// parallel and their cross product vanishes. In that case the normal of a sample nudged towards
+/// the interior of the domain is used, then the direction away from the origin, and finally +z.
+pub fn compute_normal(surface: &dyn ParametricSurface, u: f32, v: f32) -> glm::Vec3 {
+    if let Some(normal) = try_normal(surface, u, v) {
+        return normal;
+    }
+
+    let (u_start, u_end) = surface.u_domain();
+    let (v_start, v_end) = surface.v_domain();
+    let nudge = |x: f32, start: f32, end: f32| {
+        let step = (end - start) * DIFF_STEP;
+        if x + step <= end { x + step } else { x - step }
+    };
+
+    let nudged_u = nudge(u, u_start, u_end);
+    let nudged_v = nudge(v, v_start, v_end);
+
+    [(u, nudged_v), (nudged_u, v), (nudged_u, nudged_v)]
+        .iter()
+        .find_map(|&(u, v)| try_normal(surface, u, v))
+        .or_else(|| {
+            let position = surface.position(u, v);
+            (glm::length2(&position) > DEGENERATE_EPSILON).then(|| glm::normalize(&position))
+        })
+        .unwrap_or_else(|| glm::vec3(0.0, 0.0, 1.0))
+}
+
+fn try_normal(surface: &dyn ParametricSurface, u: f32, v: f32) -> Option<glm::Vec3> {
+    let (du, dv) = surface
+        .partials(u, v)
+        .unwrap_or_else(|| numeric_partials(surface, u, v));
+    let normal = glm::cross(&du, &dv);
+
+    if normal.iter().all(|c| c.is_finite()) && glm::length2(&normal) > DEGENERATE_EPSILON {
+        Some(glm::normalize(&normal))
+    } else {
+        None
+    }
+}
+
+/// Central finite differences of the position function. Samples are clamped to the domain so
+/// surfaces that are undefined outside of it never get evaluated there.
+pub fn numeric_partials(surface: &dyn ParametricSurface, u: f32, v: f32) -> (glm::Vec3, glm::Vec3) {
+    let (u_start, u_end) = surface.u_domain();
+    let (v_start, v_end) = surface.v_domain();
+
+    let hu = (u_end - u_start) * DIFF_STEP;
+    let (u0, u1) = ((u - hu).max(u_start), (u + hu).min(u_end));
+    let du = (surface.position(u1, v) - surface.position(u0, v)) / (u1 - u0);
+
+    let hv = (v_end - v_start) * DIFF_STEP;
+    let (v0, v1) = ((v - hv).max(v_start), (v + hv).min(v_end));
+    let dv = (surface.position(u, v1) - surface.position(u, v0)) / (v1 - v0);
+
+    (du, dv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parametric_surfaces::catalog::{Ellipsoid, Sphere};
+    use crate::parametric_surfaces::torus::TorusGeometry;
+    use std::f32::consts::PI;
+
+    /// The torus without its analytic partials, so normals come from finite differences.
+    struct NumericTorus(TorusGeometry);
+
+    impl ParametricSurface for NumericTorus {
+        fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+            self.0.position(u, v)
+        }
+
+        fn u_domain(&self) -> (f32, f32) {
+            self.0.u_domain()
+        }
+
+        fn v_domain(&self) -> (f32, f32) {
+            self.0.v_domain()
+        }
+    }
+
+    fn samples(surface: &dyn ParametricSurface, n: usize) -> impl Iterator<Item = (f32, f32)> {
+        let (u0, u1) = surface.u_domain();
+        let (v0, v1) = surface.v_domain();
+        (0..=n).flat_map(move |i| (0..=n).map(move |j| {
+            (u0 + (u1 - u0) * i as f32 / n as f32, v0 + (v1 - v0) * j as f32 / n as f32)
+        }))
+    }
+
+    #[test]
+    fn sphere_poles_point_along_the_axis() {
+        let sphere = Sphere::default();
+        for (v, z) in [(0.0, -1.0), (PI, 1.0)] {
+            for u in [0.0, 1.0, 2.0 * PI] {
+                let normal = compute_normal(&sphere, u, v);
+                assert!(normal.iter().all(|c| c.is_finite()));
+                assert!((glm::length(&normal) - 1.0).abs() < 1e-5);
+                assert!(glm::distance(&normal, &glm::vec3(0.0, 0.0, z)) < 1e-2, "{:?} at v = {}", normal, v);
+            }
+        }
+    }
+
+    #[test]
+    fn finite_differences_match_analytic_normals() {
+        let torus = TorusGeometry::default();
+        let numeric = NumericTorus(TorusGeometry::default());
+        for (u, v) in samples(&torus, 12) {
+            let analytic = compute_normal(&torus, u, v);
+            let estimated = compute_normal(&numeric, u, v);
+            assert!(glm::dot(&analytic, &estimated) > 0.9999, "{:?} vs {:?} at ({}, {})", analytic, estimated, u, v);
+        }
+    }
+
+    #[test]
+    fn closed_surfaces_face_outwards() {
+        let torus = TorusGeometry::default();
+        for (u, v) in samples(&torus, 12) {
+            let ring = glm::vec3(u.cos(), u.sin(), 0.0) * torus.r1;
+            assert!(glm::dot(&compute_normal(&torus, u, v), &(torus.position(u, v) - ring)) > 0.0);
+        }
+
+        let quadrics: [&dyn ParametricSurface; 2] = [&Sphere::default(), &Ellipsoid::default()];
+        for surface in quadrics {
+            for (u, v) in samples(surface, 12) {
+                assert!(glm::dot(&compute_normal(surface, u, v), &surface.position(u, v)) > 0.0);
+            }
+        }
+    }
+}
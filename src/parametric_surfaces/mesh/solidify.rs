@@ -0,0 +1,308 @@
+use super::{Indices, Mesh, Welded};
+use nalgebra_glm as glm;
+use std::collections::HashMap;
+
+/// Squared length below which averaged normals are treated as cancelling out.
+const DEGENERATE_EPSILON: f32 = 1e-12;
+
+/// Thickens a surface into a closed shell `thickness` wide, centred on the original surface.
+///
+/// The surface is copied half the thickness out along its normals and half in, the inner copy is
+/// turned inside out, and the two are joined by walls along the boundary. Where the orientation of
+/// a non-orientable surface flips across a seam, as on a Möbius strip or Klein bottle, the outer
+/// copy on one side carries on into the inner copy on the other, so closed input still gives a
+/// closed shell. Self-intersections are left as they are: the shell intersects itself wherever the
+/// surface does.
+pub fn solidify(mesh: &Mesh, thickness: f32) -> Mesh {
+    let welded = Welded::new(mesh);
+    let edges = corner_edges(&welded);
+    let vertex_count = mesh.vertex_count();
+    let half = thickness / 2.0;
+
+    let offsets = Offsets::new(mesh, &welded, &edges);
+    let mut positions = Vec::with_capacity(mesh.positions.len() * 2);
+    let mut normals = Vec::with_capacity(mesh.normals.len() * 2);
+
+    for side in [1.0, -1.0] {
+        for (id, n) in welded.ids.iter().zip(&offsets.directions) {
+            // Offsetting from the welded position keeps coincident vertices exactly together.
+            let p = &mesh.positions[*id as usize * 3..*id as usize * 3 + 3];
+            positions.extend((0..3).map(|k| p[k] + n[k] * half * side));
+            normals.extend((0..3).map(|k| n[k] * side));
+        }
+    }
+
+    let mut colors = [mesh.colors.as_slice(), mesh.colors.as_slice()].concat();
+    let mut tex_coords = [mesh.tex_coords.as_slice(), mesh.tex_coords.as_slice()].concat();
+    let mut parameters = [mesh.parameters.as_slice(), mesh.parameters.as_slice()].concat();
+    let boundary: Vec<(usize, usize)> = edges.values()
+        .filter(|uses| uses.len() == 1)
+        .map(|uses| uses[0])
+        .collect();
+    let mut indices = Vec::with_capacity(welded.triangles.len() * 6 + boundary.len() * 6);
+
+    // A corner facing against its vertex's offset direction takes the vertex's copy on the other
+    // side instead.
+    let inner = vertex_count as u32;
+    let copy = |corner: usize, outer: bool| {
+        let vertex = welded.triangles[corner / 3][corner % 3];
+        if outer != offsets.swapped[corner] { vertex } else { vertex + inner }
+    };
+    for t in 0..welded.triangles.len() {
+        let [a, b, c] = [t * 3, t * 3 + 1, t * 3 + 2];
+        indices.extend_from_slice(&[copy(a, true), copy(b, true), copy(c, true)]);
+        indices.extend_from_slice(&[copy(a, false), copy(c, false), copy(b, false)]);
+    }
+
+    // Walls get their own vertices so they shade flat instead of blending into the faces.
+    let position = |i: u32| glm::make_vec3(&positions[i as usize * 3..i as usize * 3 + 3]);
+    let mut wall_vertices: Vec<(u32, glm::Vec3)> = Vec::with_capacity(boundary.len() * 4);
+
+    for &(a, b) in &boundary {
+        // The face runs a -> b on the outer shell, so the wall must run b -> a.
+        let corners = [copy(b, true), copy(a, true), copy(a, false), copy(b, false)];
+        let along = position(corners[1]) - position(corners[0]);
+        let across = position(corners[3]) - position(corners[0]);
+        let normal = glm::normalize(&glm::cross(&along, &across));
+        let normal = if normal.iter().all(|x| x.is_finite()) { normal } else { glm::vec3(0.0, 0.0, 1.0) };
+
+        let first = (positions.len() / 3 + wall_vertices.len()) as u32;
+        wall_vertices.extend(corners.iter().map(|&i| (i, normal)));
+        indices.extend_from_slice(&[first, first + 1, first + 2, first, first + 2, first + 3]);
+    }
+
+    for (source, normal) in wall_vertices {
+        let i = source as usize;
+        let original = i % vertex_count;
+        positions.extend_from_slice(&[positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]]);
+        normals.extend_from_slice(&[normal.x, normal.y, normal.z]);
+        colors.extend_from_slice(&mesh.colors[original * 3..original * 3 + 3]);
+        tex_coords.extend_from_slice(&mesh.tex_coords[original * 2..original * 2 + 2]);
+        parameters.extend_from_slice(&mesh.parameters[original * 2..original * 2 + 2]);
+    }
+
+    let vertex_count = positions.len() / 3;
+    Mesh {
+        positions, normals, colors, tex_coords, parameters,
+        indices: Indices::new(indices, vertex_count),
+        periodic: mesh.periodic,
+    }
+}
+
+/// Every welded edge with the triangle corners, as `3 * triangle + k`, at the start and end of each
+/// use of it.
+fn corner_edges(welded: &Welded) -> HashMap<(u32, u32), Vec<(usize, usize)>> {
+    let mut edges: HashMap<(u32, u32), Vec<(usize, usize)>> = HashMap::new();
+
+    for (t, triangle) in welded.triangles.iter().enumerate() {
+        for k in 0..3 {
+            let (a, b) = (welded.ids[triangle[k] as usize], welded.ids[triangle[(k + 1) % 3] as usize]);
+            edges.entry((a.min(b), a.max(b))).or_default().push((t * 3 + k, t * 3 + (k + 1) % 3));
+        }
+    }
+
+    edges
+}
+
+/// Which way to offset each vertex of a mesh.
+///
+/// Corners of triangles at the same point that are joined up around it, such as across a seam or
+/// at a sphere's pole, form a fan sharing one averaged direction, so they stay together after
+/// offsetting. Across a non-orientable seam, and where a degenerate row of the parameterisation
+/// turns the triangles at one vertex to face opposite ways, part of a fan faces against the rest
+/// and offsets the opposite way. Sheets that only pass through the same point, as along a
+/// self-intersection, are offset independently.
+struct Offsets {
+    directions: Vec<glm::Vec3>,
+    /// For every triangle corner, whether it faces against its vertex's direction.
+    swapped: Vec<bool>,
+}
+
+impl Offsets {
+    fn new(mesh: &Mesh, welded: &Welded, edges: &HashMap<(u32, u32), Vec<(usize, usize)>>) -> Self {
+        let normals: Vec<glm::Vec3> = mesh.normals.chunks_exact(3).map(glm::make_vec3).collect();
+        let positions: Vec<glm::Vec3> = mesh.positions.chunks_exact(3).map(glm::make_vec3).collect();
+        let vertex = |corner: usize| welded.triangles[corner / 3][corner % 3] as usize;
+        let mut fans = Fans::new(welded.triangles.len() * 3);
+
+        // Triangles carrying on across an edge meet around both of its ends. They face the same way
+        // if they run along the edge in opposite directions.
+        for uses in edges.values() {
+            for ((a, b), (c, d)) in continuations(uses, &positions, vertex) {
+                let flipped = welded.ids[vertex(a)] == welded.ids[vertex(c)];
+                let (c, d) = if flipped { (c, d) } else { (d, c) };
+                fans.join(a, c, flipped);
+                fans.join(b, d, flipped);
+            }
+        }
+
+        // Corners of one vertex usually face the same way, which also joins up the sheets on either
+        // side of a non-manifold edge. Fans turning them opposite ways take precedence.
+        let mut first_corners: Vec<Option<usize>> = vec![None; normals.len()];
+        for corner in 0..welded.triangles.len() * 3 {
+            match first_corners[vertex(corner)] {
+                Some(first) => fans.join(first, corner, false),
+                None => first_corners[vertex(corner)] = Some(corner),
+            }
+        }
+
+        // The vertex normal, turned to face the same way as the corner's triangle.
+        let corner_normal = |corner: usize| {
+            let [a, b, c] = welded.triangles[corner / 3].map(|i| positions[i as usize]);
+            let normal = normals[vertex(corner)];
+            if glm::dot(&normal, &glm::cross(&(b - a), &(c - a))) < 0.0 { -normal } else { normal }
+        };
+        let sign = |flipped: bool| if flipped { -1.0 } else { 1.0 };
+        let mut sums = vec![glm::vec3(0.0, 0.0, 0.0); fans.len()];
+        for corner in 0..fans.len() {
+            let (root, flipped) = fans.find(corner);
+            sums[root] += corner_normal(corner) * sign(flipped);
+        }
+
+        let directions = first_corners.iter().enumerate().map(|(i, first)| {
+            let Some(first) = *first else { return normals[i] };
+            let (root, flipped) = fans.find(first);
+            // Normals around a sharp tip can cancel out; any shared direction keeps the fan together.
+            let direction = if glm::length2(&sums[root]) > DEGENERATE_EPSILON {
+                glm::normalize(&sums[root])
+            } else {
+                corner_normal(root)
+            };
+            direction * sign(flipped)
+        }).collect();
+
+        let swapped = (0..fans.len()).map(|corner| {
+            let first = first_corners[vertex(corner)].unwrap();
+            fans.find(corner).1 != fans.find(first).1
+        }).collect();
+
+        Self { directions, swapped }
+    }
+}
+
+/// Pairs up the uses of an edge whose triangles carry on into each other: the two uses of a
+/// manifold edge or, where sheets cross or fold onto each other along the edge, uses of the same
+/// unwelded edge first and whichever remain by the flattest angle between them.
+fn continuations(
+    uses: &[(usize, usize)],
+    positions: &[glm::Vec3],
+    vertex: impl Fn(usize) -> usize,
+) -> Vec<((usize, usize), (usize, usize))> {
+    let mut pairs = vec![];
+    let mut uses = uses.to_vec();
+    if uses.len() > 2 {
+        let key = |(a, b): (usize, usize)| (vertex(a).min(vertex(b)), vertex(a).max(vertex(b)));
+        let mut i = 0;
+        while i < uses.len() {
+            match (i + 1..uses.len()).find(|&j| key(uses[i]) == key(uses[j])) {
+                Some(j) => {
+                    pairs.push((uses[i], uses.remove(j)));
+                    uses.remove(i);
+                }
+                None => i += 1,
+            }
+        }
+    }
+    if uses.len() <= 2 {
+        pairs.extend(uses.first().zip(uses.get(1)).map(|(&a, &b)| (a, b)));
+        return pairs;
+    }
+
+    // Direction each use's triangle extends in away from the edge.
+    let (start, end) = (positions[vertex(uses[0].0)], positions[vertex(uses[0].1)]);
+    let along = glm::normalize(&(end - start));
+    let mut sides: Vec<((usize, usize), glm::Vec3)> = uses.iter().map(|&(a, b)| {
+        let opposite = positions[vertex(a / 3 * 3 + (a + 2) % 3)] - start;
+        ((a, b), glm::normalize(&(opposite - along * glm::dot(&opposite, &along))))
+    }).collect();
+
+    while sides.len() >= 2 {
+        let mut flattest = (0, 1);
+        for i in 0..sides.len() {
+            for j in i + 1..sides.len() {
+                let (k, l) = flattest;
+                if glm::dot(&sides[i].1, &sides[j].1) < glm::dot(&sides[k].1, &sides[l].1) {
+                    flattest = (i, j);
+                }
+            }
+        }
+        let second = sides.remove(flattest.1).0;
+        let first = sides.remove(flattest.0).0;
+        pairs.push((first, second));
+    }
+    pairs
+}
+
+/// Union-find over triangle corners, grouping them into fans. Each corner remembers whether it faces
+/// against its parent.
+struct Fans {
+    parents: Vec<usize>,
+    flipped: Vec<bool>,
+}
+
+impl Fans {
+    fn new(corner_count: usize) -> Self {
+        Self { parents: (0..corner_count).collect(), flipped: vec![false; corner_count] }
+    }
+
+    fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Root of the fan `corner` belongs to, and whether `corner` faces against it.
+    fn find(&mut self, corner: usize) -> (usize, bool) {
+        let parent = self.parents[corner];
+        if parent == corner {
+            return (corner, false);
+        }
+        let (root, parent_flipped) = self.find(parent);
+        self.parents[corner] = root;
+        self.flipped[corner] ^= parent_flipped;
+        (root, self.flipped[corner])
+    }
+
+    /// Puts `a` and `b` in one fan, `b` facing against `a` if `flipped`. Corners already in the
+    /// same fan are left as they are.
+    fn join(&mut self, a: usize, b: usize, flipped: bool) {
+        let (root_a, flipped_a) = self.find(a);
+        let (root_b, flipped_b) = self.find(b);
+        if root_a != root_b {
+            self.parents[root_b] = root_a;
+            self.flipped[root_b] = flipped_a ^ flipped_b ^ flipped;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parametric_surfaces::catalog;
+    use crate::parametric_surfaces::mesh::tessellate;
+
+    #[test]
+    fn solidified_catalog_surfaces_are_watertight() {
+        for name in catalog::NAMES {
+            let surface = catalog::by_name(name).unwrap();
+            for (nu, nv) in [(48, 48), (17, 9)] {
+                let solid = solidify(&tessellate(surface.as_ref(), nu, nv), 0.02);
+                if let Err(e) = Welded::new(&solid).check_watertight() {
+                    panic!("{} at {}x{}: {}", name, nu, nv, e);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn orientable_surfaces_are_offset_along_their_normals() {
+        let surface = catalog::by_name("Torus").unwrap();
+        let mesh = tessellate(surface.as_ref(), 24, 24);
+        let solid = solidify(&mesh, 0.1);
+
+        for i in 0..mesh.vertex_count() {
+            for k in 0..3 {
+                let expected = mesh.positions[i * 3 + k] + mesh.normals[i * 3 + k] * 0.05;
+                assert!((solid.positions[i * 3 + k] - expected).abs() < 1e-5);
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use super::{compute_normal, Grid, Indices, Mesh};
+use crate::parametric_surfaces::ParametricSurface;
+
+const COLOR_STEP: f32 = 0.5;
+
+/// Samples `surface` on an `nu` by `nv` grid of quads and splits each quad into two triangles.
+///
+/// Along a periodic direction the last row/column of samples would coincide with the first, so it
+/// is dropped and the final quads index back into the first row/column instead. Neighbouring
+/// triangles across the seam therefore share vertices and no cracks can open up.
+///
+/// The mesh always has `(nu + 1) * (nv + 1)` vertices, with one fewer row and/or column for each
+/// periodic direction (down to `nu * nv` when both are periodic), and exactly `2 * nu * nv`
+/// triangles. `nu` and `nv` are at least 1, and at least 3 along a periodic direction.
+pub fn tessellate(surface: &dyn ParametricSurface, nu: usize, nv: usize) -> Mesh {
+    let u_grid = Grid::new(surface.u_domain(), nu, surface.periodic_u());
+    let v_grid = Grid::new(surface.v_domain(), nv, surface.periodic_v());
+    let vertex_count = u_grid.samples() * v_grid.samples();
+
+    let mut positions = Vec::with_capacity(vertex_count * 3);
+    let mut normals = Vec::with_capacity(vertex_count * 3);
+    let mut colors = Vec::with_capacity(vertex_count * 3);
+    let mut tex_coords = Vec::with_capacity(vertex_count * 2);
+    let mut parameters = Vec::with_capacity(vertex_count * 2);
+
+    for (j, v) in v_grid.iter() {
+        for (i, u) in u_grid.iter() {
+            let point = surface.position(u, v);
+            let normal = compute_normal(surface, u, v);
+
+            positions.extend_from_slice(&[point.x, point.y, point.z]);
+            normals.extend_from_slice(&[normal.x, normal.y, normal.z]);
+            colors.extend_from_slice(&[point.x + COLOR_STEP, point.y + COLOR_STEP, point.z + COLOR_STEP]);
+            tex_coords.extend_from_slice(&[u_grid.fraction(i), v_grid.fraction(j)]);
+            parameters.extend_from_slice(&[u, v]);
+        }
+    }
+
+    let index = |i: usize, j: usize| (v_grid.wrap(j) * u_grid.samples() + u_grid.wrap(i)) as u32;
+    let mut indices = Vec::with_capacity(u_grid.segments() * v_grid.segments() * 6);
+
+    for j in 0..v_grid.segments() {
+        for i in 0..u_grid.segments() {
+            let a = index(i, j);
+            let b = index(i + 1, j);
+            let c = index(i + 1, j + 1);
+            let d = index(i, j + 1);
+
+            indices.extend_from_slice(&[a, b, c, a, c, d]);
+        }
+    }
+
+    Mesh {
+        positions,
+        normals,
+        colors,
+        tex_coords,
+        parameters,
+        indices: Indices::new(indices, vertex_count),
+        periodic: (u_grid.periodic(), v_grid.periodic()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parametric_surfaces::mesh::Welded;
+    use crate::parametric_surfaces::torus::TorusGeometry;
+    use nalgebra_glm as glm;
+
+    /// The unit square, open in both directions.
+    struct Plane;
+
+    impl ParametricSurface for Plane {
+        fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+            glm::vec3(u, v, 0.0)
+        }
+
+        fn u_domain(&self) -> (f32, f32) {
+            (0.0, 1.0)
+        }
+
+        fn v_domain(&self) -> (f32, f32) {
+            (0.0, 1.0)
+        }
+    }
+
+    /// A cylinder, periodic in u only.
+    struct Cylinder;
+
+    impl ParametricSurface for Cylinder {
+        fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+            glm::vec3(u.cos(), u.sin(), v)
+        }
+
+        fn u_domain(&self) -> (f32, f32) {
+            (0.0, 2.0 * std::f32::consts::PI)
+        }
+
+        fn v_domain(&self) -> (f32, f32) {
+            (0.0, 1.0)
+        }
+
+        fn periodic_u(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn open_surfaces_have_a_vertex_per_grid_point() {
+        let mesh = tessellate(&Plane, 5, 3);
+        assert_eq!(mesh.vertex_count(), 6 * 4);
+        assert_eq!(mesh.triangle_count(), 2 * 5 * 3);
+        assert_eq!(mesh.periodic, (false, false));
+    }
+
+    #[test]
+    fn periodic_directions_drop_their_last_row() {
+        let cylinder = tessellate(&Cylinder, 5, 3);
+        assert_eq!(cylinder.vertex_count(), 5 * 4);
+        assert_eq!(cylinder.triangle_count(), 2 * 5 * 3);
+
+        let torus = tessellate(&TorusGeometry::default(), 5, 3);
+        assert_eq!(torus.vertex_count(), 5 * 3);
+        assert_eq!(torus.triangle_count(), 2 * 5 * 3);
+        assert_eq!(torus.periodic, (true, true));
+    }
+
+    #[test]
+    fn closing_quads_share_the_first_columns_vertices() {
+        let (nu, nv) = (4, 3);
+        let mesh = tessellate(&Cylinder, nu, nv);
+        let indices: Vec<u32> = mesh.indices.iter().collect();
+
+        // Quads are emitted row by row as [a, b, c, a, c, d]; the last quad of the first row runs
+        // from column nu - 1 back to column 0.
+        let last_quad = &indices[(nu - 1) * 6..nu * 6];
+        assert_eq!(last_quad, &[3, 0, 4, 3, 4, 7]);
+        assert!(indices.iter().all(|&i| (i as usize) < mesh.vertex_count()));
+        assert_eq!(Welded::new(&mesh).boundary_edges().len(), 2 * nu);
+    }
+
+    #[test]
+    fn coarse_periodic_grids_do_not_collapse() {
+        let mesh = tessellate(&TorusGeometry::default(), 1, 1);
+        assert_eq!(mesh.vertex_count(), 3 * 3);
+        assert_eq!(mesh.triangle_count(), 2 * 3 * 3);
+        assert_eq!(Welded::new(&mesh).triangles.len(), mesh.triangle_count());
+        assert!(Welded::new(&mesh).check_watertight().is_ok());
+    }
+}
@@ -0,0 +1,194 @@
+use super::Mesh;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Vertices closer than this along every axis are treated as the same point.
+const WELD_EPSILON: f32 = 1e-5;
+
+/// Edge between two vertices of a [`Mesh`], by index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edge {
+    pub a: u32,
+    pub b: u32,
+}
+
+/// Why a mesh doesn't enclose a solid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TopologyError {
+    /// Edges used by only one triangle, i.e. holes in the surface.
+    pub boundary_edges: Vec<Edge>,
+    /// Edges shared by more than two triangles.
+    pub non_manifold_edges: Vec<Edge>,
+}
+
+impl fmt::Display for TopologyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "mesh is not watertight: {} boundary edge(s), {} non-manifold edge(s)",
+            self.boundary_edges.len(), self.non_manifold_edges.len()
+        )
+    }
+}
+
+/// Mesh connectivity with coincident vertices merged. Tessellation keeps separate vertices along
+/// seams that only meet geometrically (sphere poles, the twist of a Möbius strip, ...), so edges
+/// are matched by position rather than by index.
+pub struct Welded {
+    /// Index of the first vertex at the same position, for every vertex of the mesh.
+    pub ids: Vec<u32>,
+    /// Triangles of the mesh that don't collapse to a line or point once welded, by original index.
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl Welded {
+    pub fn new(mesh: &Mesh) -> Self {
+        // Points are bucketed into cells one epsilon wide; a match can only lie in the same cell or
+        // one of its neighbours, however close to a cell boundary the point falls.
+        let mut cells: HashMap<[i64; 3], Vec<u32>> = HashMap::new();
+        let points: Vec<[f32; 3]> = mesh.positions.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect();
+        let near = |a: &[f32; 3], b: &[f32; 3]| (0..3).all(|k| (a[k] - b[k]).abs() <= WELD_EPSILON);
+
+        let ids = points.iter().enumerate().map(|(i, point)| {
+            let cell = point.map(|x| (x / WELD_EPSILON).floor() as i64);
+            let neighbours = (0..27).map(|n| [cell[0] + n % 3 - 1, cell[1] + n / 3 % 3 - 1, cell[2] + n / 9 - 1]);
+            let found = neighbours
+                .filter_map(|key| cells.get(&key))
+                .flatten()
+                .find(|&&j| near(point, &points[j as usize]))
+                .copied();
+
+            found.unwrap_or_else(|| {
+                cells.entry(cell).or_default().push(i as u32);
+                i as u32
+            })
+        }).collect::<Vec<u32>>();
+
+        let indices: Vec<u32> = mesh.indices.iter().collect();
+        let triangles = indices.chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .filter(|t| {
+                let [a, b, c] = t.map(|i| ids[i as usize]);
+                a != b && b != c && c != a
+            })
+            .collect();
+
+        Self { ids, triangles }
+    }
+
+    /// Every welded edge with the directed edges, by original index, of the triangles using it.
+    fn edges(&self) -> HashMap<(u32, u32), Vec<Edge>> {
+        let mut edges: HashMap<(u32, u32), Vec<Edge>> = HashMap::new();
+
+        for triangle in &self.triangles {
+            for k in 0..3 {
+                let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+                let (wa, wb) = (self.ids[a as usize], self.ids[b as usize]);
+                edges.entry((wa.min(wb), wa.max(wb))).or_default().push(Edge { a, b });
+            }
+        }
+
+        edges
+    }
+
+    /// Directed edges, by original index, that only one triangle uses.
+    pub fn boundary_edges(&self) -> Vec<Edge> {
+        let mut boundary: Vec<Edge> = self.edges().into_values()
+            .filter(|uses| uses.len() == 1)
+            .map(|uses| uses[0])
+            .collect();
+        boundary.sort_by_key(|edge| (edge.a, edge.b));
+        boundary
+    }
+
+    /// Checks that every edge is shared by exactly two triangles.
+    pub fn check_watertight(&self) -> Result<(), TopologyError> {
+        let mut boundary_edges = vec![];
+        let mut non_manifold_edges = vec![];
+
+        for uses in self.edges().into_values() {
+            match uses.len() {
+                1 => boundary_edges.push(uses[0]),
+                2 => (),
+                _ => non_manifold_edges.push(uses[0]),
+            }
+        }
+
+        if boundary_edges.is_empty() && non_manifold_edges.is_empty() {
+            return Ok(());
+        }
+
+        boundary_edges.sort_by_key(|edge| (edge.a, edge.b));
+        non_manifold_edges.sort_by_key(|edge| (edge.a, edge.b));
+        Err(TopologyError { boundary_edges, non_manifold_edges })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parametric_surfaces::mesh::Indices;
+
+    fn mesh(positions: &[[f32; 3]], indices: &[u32]) -> Mesh {
+        Mesh {
+            positions: positions.iter().flatten().copied().collect(),
+            indices: Indices::new(indices.to_vec(), positions.len()),
+            ..Default::default()
+        }
+    }
+
+    const TETRAHEDRON: [[f32; 3]; 4] = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    const FACES: [u32; 12] = [0, 2, 1, 0, 1, 3, 0, 3, 2, 1, 2, 3];
+
+    #[test]
+    fn closed_meshes_are_watertight() {
+        assert_eq!(Welded::new(&mesh(&TETRAHEDRON, &FACES)).check_watertight(), Ok(()));
+    }
+
+    #[test]
+    fn coincident_vertices_are_welded() {
+        // Every face with its own vertices, the last one nudged by less than the weld epsilon.
+        let mut positions: Vec<[f32; 3]> = FACES.iter().map(|&i| TETRAHEDRON[i as usize]).collect();
+        positions[11][2] += WELD_EPSILON / 2.0;
+        let indices: Vec<u32> = (0..12).collect();
+
+        let welded = Welded::new(&mesh(&positions, &indices));
+        assert_eq!(welded.ids, [0, 1, 2, 0, 2, 5, 0, 5, 1, 2, 1, 5]);
+        assert_eq!(welded.check_watertight(), Ok(()));
+    }
+
+    #[test]
+    fn holes_are_boundary_edges() {
+        let welded = Welded::new(&mesh(&TETRAHEDRON, &FACES[..9]));
+        let expected = vec![Edge { a: 1, b: 3 }, Edge { a: 2, b: 1 }, Edge { a: 3, b: 2 }];
+        assert_eq!(welded.boundary_edges(), expected);
+        assert_eq!(
+            welded.check_watertight(),
+            Err(TopologyError { boundary_edges: expected, non_manifold_edges: vec![] })
+        );
+    }
+
+    #[test]
+    fn fins_are_non_manifold() {
+        // A fifth vertex adds a third triangle on the edge 1-2.
+        let mut positions = TETRAHEDRON.to_vec();
+        positions.push([1.0, 1.0, 0.0]);
+        let mut indices = FACES.to_vec();
+        indices.extend([1, 4, 2]);
+
+        let error = Welded::new(&mesh(&positions, &indices)).check_watertight().unwrap_err();
+        assert_eq!(error.non_manifold_edges.len(), 1);
+        assert_eq!(error.boundary_edges, [Edge { a: 1, b: 4 }, Edge { a: 4, b: 2 }]);
+    }
+
+    #[test]
+    fn collapsed_triangles_are_dropped() {
+        let mut positions = TETRAHEDRON.to_vec();
+        positions.push([0.0, 0.0, 0.0]);
+        let mut indices = FACES.to_vec();
+        indices.extend([0, 4, 1]);
+
+        let welded = Welded::new(&mesh(&positions, &indices));
+        assert_eq!(welded.triangles.len(), 4);
+        assert_eq!(welded.check_watertight(), Ok(()));
+    }
+}
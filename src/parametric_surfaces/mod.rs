@@ -1,2 +1,64 @@
+pub mod catalog;
 pub mod cube;
+pub mod expression;
+pub mod mesh;
+#[cfg(feature = "webgl")]
+pub mod renderer;
 pub mod torus;
+pub mod triforce;
+
+use nalgebra_glm as glm;
+use std::f32::consts::PI;
+
+/// A surface described by a position function over a rectangular (u, v) domain. Implementors only
+/// need to supply the equations; sampling, buffering and drawing are handled by `SurfaceRenderer`.
+pub trait ParametricSurface {
+    /// Point on the surface at parameters (u, v).
+    fn position(&self, u: f32, v: f32) -> glm::Vec3;
+
+    /// Range of the u parameter as (start, end).
+    fn u_domain(&self) -> (f32, f32);
+
+    /// Range of the v parameter as (start, end).
+    fn v_domain(&self) -> (f32, f32);
+
+    /// Analytic partial derivatives (∂p/∂u, ∂p/∂v) at (u, v), used to compute normals. Surfaces that
+    /// return `None` get central finite differences of [`ParametricSurface::position`] instead.
+    fn partials(&self, _u: f32, _v: f32) -> Option<(glm::Vec3, glm::Vec3)> {
+        None
+    }
+
+    /// Whether the surface wraps around in u, i.e. `position(u_start, v) == position(u_end, v)`.
+    fn periodic_u(&self) -> bool {
+        false
+    }
+
+    /// Whether the surface wraps around in v, i.e. `position(u, v_start) == position(u, v_end)`.
+    fn periodic_v(&self) -> bool {
+        false
+    }
+}
+
+/// Rotation a surface is animated with as `dtheta` advances: `phase + dtheta` radians about `axis`.
+#[derive(Clone, Copy, Debug)]
+pub struct Spin {
+    pub axis: glm::Vec3,
+    pub phase: f32,
+}
+
+impl Default for Spin {
+    fn default() -> Self {
+        Self { axis: glm::vec3(0.0, 1.0, 1.0), phase: PI / 4.0 }
+    }
+}
+
+impl Spin {
+    pub fn matrix(&self, dtheta: f32) -> glm::TMat4<f32> {
+        let theta = (self.phase + dtheta) % (2.0 * PI);
+        glm::rotate(&glm::TMat4::identity(), theta, &self.axis)
+    }
+
+    pub fn rotation(&self, dtheta: f32) -> glm::Quat {
+        glm::quat_angle_axis(self.phase + dtheta, &glm::normalize(&self.axis))
+    }
+}
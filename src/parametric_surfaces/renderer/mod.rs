@@ -0,0 +1,283 @@
+use crate::camera::Camera;
+use crate::error::{number, Error};
+use crate::lighting::{Lighting, Material};
+use crate::parametric_surfaces::{ParametricSurface, Spin};
+use crate::parametric_surfaces::mesh::{self, Bounds, Indices, Mesh};
+use crate::renderer::{self, Drawable, Frame};
+use crate::{gl_context, shader, buf_f32, buf_u16, buf_u32};
+use js_sys::{JsString, Number};
+use nalgebra_glm as glm;
+use std::rc::Rc;
+use web_sys::WebGlBuffer as Buffer;
+use web_sys::WebGlRenderingContext as GL;
+
+/// Default number of quads the surface is split into along each of u and v.
+const SUBDIVISIONS: usize = 72;
+/// Most quads along each of u and v, about two million triangles in all.
+const MAX_SUBDIVISIONS: usize = 1024;
+/// Distance of the camera from the surface when drawn on its own.
+const CAMERA_DISTANCE: f32 = 2.0;
+/// Inputs surfaces provide to the uber-shader, and the defines user-supplied shaders are built with.
+const FEATURES: shader::Features = shader::Features { normals: true, colors: true, tex_coords: false };
+
+/// Draws any [`ParametricSurface`] into a canvas. Surface modules wrap this in a `#[wasm_bindgen]`
+/// struct since exported types can't be generic.
+pub struct SurfaceRenderer {
+    pub camera: Camera,
+    gl: GL,
+    surface_shader: Rc<shader::Shader>,
+    buffers: SurfaceBuffers,
+    attributes: SurfaceAttributes,
+    subdivisions: (usize, usize),
+    mesh: Mesh,
+    bounds: Option<Bounds>,
+    indices_count: i32,
+    indices_type: u32,
+    /// Used unless the frame brings scene lights.
+    pub lighting: Lighting,
+    pub material: Material,
+}
+
+/// GPU buffers are created once per renderer and refilled whenever the surface changes.
+struct SurfaceBuffers {
+    positions: Buffer,
+    normals: Buffer,
+    colors: Buffer,
+    indices: Buffer,
+}
+
+/// Attribute locations, rebound before every draw since other objects may share the context.
+/// User-supplied shaders may leave out normals and colours.
+struct SurfaceAttributes {
+    position: u32,
+    normal: Option<u32>,
+    color: Option<u32>,
+}
+
+type SurfaceResult<T> = Result<T, Error>;
+
+impl SurfaceRenderer {
+    pub fn try_new(canvas_id: JsString, surface: &dyn ParametricSurface) -> SurfaceResult<Self> {
+        let gl = gl_context::init_gl_context(canvas_id)?;
+        Self::try_with_context(gl, surface)
+    }
+
+    /// Builds a renderer drawing into an existing context, e.g. one owned by a
+    /// [`Renderer`](crate::renderer::Renderer).
+    pub fn try_with_context(gl: GL, surface: &dyn ParametricSurface) -> SurfaceResult<Self> {
+        let surface_shader = shader::Shader::uber(&gl, FEATURES)?;
+        let buffers = Self::init_buffers(&gl)?;
+        let attributes = Self::init_attributes(&surface_shader)?;
+
+        let mut renderer = Self {
+            camera: Camera::new(CAMERA_DISTANCE), gl, surface_shader, buffers, attributes,
+            subdivisions: (SUBDIVISIONS, SUBDIVISIONS), mesh: Mesh::default(), bounds: None,
+            indices_count: 0, indices_type: GL::UNSIGNED_SHORT,
+            lighting: Lighting::default(), material: Material::default()
+        };
+        renderer.try_set_surface(surface)?;
+
+        Ok(renderer)
+    }
+
+    /// Sets how many quads the next call to [`SurfaceRenderer::try_set_surface`] splits the surface into
+    /// along u and v. Fails if either is over 1024.
+    pub fn set_subdivisions(&mut self, u_subdivisions: usize, v_subdivisions: usize) -> SurfaceResult<()> {
+        if u_subdivisions.max(v_subdivisions) > MAX_SUBDIVISIONS {
+            return Err(Error::InvalidArgument(format!(
+                "Can't split a surface into {}x{} quads, the most is {} along each side",
+                u_subdivisions, v_subdivisions, MAX_SUBDIVISIONS
+            )));
+        }
+        self.subdivisions = (u_subdivisions.max(1), v_subdivisions.max(1));
+        Ok(())
+    }
+
+    /// Re-tessellates `surface` and uploads the result into the existing GPU buffers.
+    pub fn try_set_surface(&mut self, surface: &dyn ParametricSurface) -> SurfaceResult<()> {
+        let (nu, nv) = self.subdivisions;
+        let mesh = mesh::tessellate(surface, nu, nv);
+        let gl = &self.gl;
+
+        // Checked before uploading anything, so a failure leaves the previous surface intact.
+        let (indices_data, indices_type) = match &mesh.indices {
+            Indices::U16(indices) => (buf_u16!(indices), GL::UNSIGNED_SHORT),
+            Indices::U32(indices) => {
+                gl.get_extension("OES_element_index_uint")?
+                    .ok_or_else(|| Error::Unsupported("32-bit indices are not supported by this device.".into()))?;
+                (buf_u32!(indices), GL::UNSIGNED_INT)
+            }
+        };
+
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.buffers.positions));
+        gl.buffer_data_with_opt_array_buffer(
+            GL::ARRAY_BUFFER, Some(&buf_f32!(&mesh.positions)), GL::STATIC_DRAW
+        );
+
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.buffers.normals));
+        gl.buffer_data_with_opt_array_buffer(
+            GL::ARRAY_BUFFER, Some(&buf_f32!(&mesh.normals)), GL::STATIC_DRAW
+        );
+
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.buffers.colors));
+        gl.buffer_data_with_opt_array_buffer(
+            GL::ARRAY_BUFFER, Some(&buf_f32!(&mesh.colors)), GL::STATIC_DRAW
+        );
+
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&self.buffers.indices));
+        gl.buffer_data_with_opt_array_buffer(
+            GL::ELEMENT_ARRAY_BUFFER, Some(&indices_data), GL::STATIC_DRAW
+        );
+
+        self.indices_count = mesh.indices.len() as i32;
+        self.indices_type = indices_type;
+
+        // The surface spins about the origin, so it can reach as far as its furthest vertex along
+        // any axis.
+        let reach = mesh.positions.chunks_exact(3)
+            .map(|p| glm::length(&glm::vec3(p[0], p[1], p[2])))
+            .fold(0.0, f32::max);
+        self.bounds = mesh.bounds().map(|_| Bounds::cube(reach));
+        self.mesh = mesh;
+
+        Ok(())
+    }
+
+    /// The mesh currently on the GPU, as last tessellated by [`SurfaceRenderer::try_set_surface`].
+    pub fn mesh(&self) -> &Mesh {
+        &self.mesh
+    }
+
+    pub fn try_render(&self, canvas_width: Number, canvas_height: Number, dtheta: Number) -> SurfaceResult<()> {
+        let width = number(&canvas_width, "canvas_width")?;
+        let height = number(&canvas_height, "canvas_height")?;
+        let frame = Frame::new(&self.camera, width, height, number(&dtheta, "dtheta")? as f32);
+
+        renderer::clear(&self.gl);
+        self.draw(&frame, &glm::TMat4::identity())?;
+        self.gl.flush();
+
+        Ok(())
+    }
+
+    /// RGBA pixels of the frame at `dtheta`, rendered offscreen at `width` x `height`.
+    pub fn capture(&self, width: u32, height: u32, dtheta: f32) -> SurfaceResult<Vec<u8>> {
+        renderer::read_frame(&self.gl, &self.camera, width, height, dtheta, |frame| {
+            self.draw(frame, &glm::TMat4::identity())
+        })
+    }
+
+    fn bind_attribute(&self, buffer: &Buffer, attr: u32) {
+        self.gl.bind_buffer(GL::ARRAY_BUFFER, Some(buffer));
+        self.gl.vertex_attrib_pointer_with_i32(attr, 3, GL::FLOAT, false, 0, 0);
+        self.gl.enable_vertex_attrib_array(attr);
+    }
+
+    fn init_buffers(gl: &GL) -> SurfaceResult<SurfaceBuffers> {
+        let positions = gl.create_buffer().ok_or(Error::Allocation("positions vbo"))?;
+        let normals = gl.create_buffer().ok_or(Error::Allocation("normals vbo"))?;
+        let colors = gl.create_buffer().ok_or(Error::Allocation("colors vbo"))?;
+        let indices = gl.create_buffer().ok_or(Error::Allocation("indices ebo"))?;
+
+        Ok(SurfaceBuffers { positions, normals, colors, indices })
+    }
+
+    fn init_attributes(shader: &shader::Shader) -> SurfaceResult<SurfaceAttributes> {
+        let optional = |attribute: &str| if shader.has_attribute(attribute) {
+            shader.attribute_location(attribute, GL::FLOAT_VEC3).map(Some)
+        } else {
+            Ok(None)
+        };
+        Ok(SurfaceAttributes {
+            position: shader.attribute_location("position", GL::FLOAT_VEC3)?,
+            normal: optional("normal")?,
+            color: optional("color")?,
+        })
+    }
+
+    /// Sets a uniform unless the program leaves it out, as user-supplied shaders may.
+    fn set_uniform<T: shader::UniformValue + ?Sized>(&self, uniform: &str, value: &T) -> SurfaceResult<()> {
+        if self.surface_shader.has_uniform(uniform) {
+            self.surface_shader.set_uniform(&self.gl, uniform, value)?;
+        }
+        Ok(())
+    }
+
+    /// Draws with a program built from user-supplied GLSL, e.g. from a live editor. The sources see
+    /// the same `HAS_*` defines and `#include` library as the built-in shader, must declare a
+    /// `vec3 position` attribute and may use any of the `normal` and `color` attributes and the
+    /// built-in shader's uniforms. On failure the current program is kept and the compile errors
+    /// are returned.
+    pub fn try_set_shader_sources(&mut self, vertex: &str, fragment: &str) -> SurfaceResult<()> {
+        let defines = FEATURES.defines();
+        let vertex = shader::preprocess("vertex", vertex, &defines)?;
+        let fragment = shader::preprocess("fragment", fragment, &defines)?;
+        let program = shader::Shader::new(&self.gl, &vertex, &fragment)?;
+        let attributes = Self::init_attributes(&program)?;
+
+        self.surface_shader = Rc::new(program);
+        self.attributes = attributes;
+        Ok(())
+    }
+
+    /// Goes back to the built-in shader after [`SurfaceRenderer::try_set_shader_sources`].
+    pub fn try_reset_shader(&mut self) -> SurfaceResult<()> {
+        let program = shader::Shader::uber(&self.gl, FEATURES)?;
+        self.attributes = Self::init_attributes(&program)?;
+        self.surface_shader = program;
+        Ok(())
+    }
+
+    /// Vertex and fragment source of the built-in shader, before preprocessing.
+    pub fn default_shader_sources() -> (&'static str, &'static str) {
+        let source = |name| shader::library::source(name).unwrap_or_default();
+        (source("uber.vert"), source("uber.frag"))
+    }
+
+    /// Rotation the surface is animated with as `dtheta` advances.
+    pub fn spin(&self) -> Spin {
+        Spin::default()
+    }
+}
+
+impl Drawable for SurfaceRenderer {
+    fn draw(&self, frame: &Frame, model: &glm::TMat4<f32>) -> SurfaceResult<()> {
+        let model = model * self.spin().matrix(frame.dtheta);
+        let normal = glm::inverse_transpose(frame.view * model);
+
+        self.surface_shader.use_shader(&self.gl);
+        let lighting = frame.lighting.as_ref().unwrap_or(&self.lighting);
+        self.set_uniform("ambientLight", &lighting.ambient)?;
+        self.set_uniform("lightCount", &(lighting.lights().len() as i32))?;
+        self.set_uniform("lights", &lighting.in_view(&frame.view)[..])?;
+        self.set_uniform("material", &self.material)?;
+        self.set_uniform("m", &model)?;
+        self.set_uniform("v", &frame.view)?;
+        self.set_uniform("p", &frame.projection)?;
+        self.set_uniform("n", &normal)?;
+
+        let attributes = [
+            (&self.buffers.positions, Some(self.attributes.position)),
+            (&self.buffers.normals, self.attributes.normal),
+            (&self.buffers.colors, self.attributes.color),
+        ];
+        for (buffer, attr) in attributes {
+            if let Some(attr) = attr {
+                self.bind_attribute(buffer, attr);
+            }
+        }
+
+        self.gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&self.buffers.indices));
+        self.gl.draw_elements_with_i32(GL::TRIANGLES, self.indices_count, self.indices_type, 0);
+
+        for attr in attributes.iter().filter_map(|(_, attr)| *attr) {
+            self.gl.disable_vertex_attrib_array(attr);
+        }
+
+        Ok(())
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+}
@@ -1,46 +1,51 @@
+use crate::parametric_surfaces::ParametricSurface;
+use nalgebra_glm as glm;
 use std::f32::consts::PI;
 
 const PI2: f32 = PI * 2.0;
-const STEP: f32 = PI * (5.0 / 180.0);
-const COLOR_STEP: f32 = 0.5;
 const R1: f32 = 0.5;
 const R2: f32 = 0.2;
 
-pub fn compute_vertices() -> (Vec<f32>, Vec<f32>, i32)  {
-    let sin = |rads: f32| rads.sin();
-    let cos = |rads: f32| rads.cos();
-
-    // Parametric equations:
-    let fx = |u: f32, v: f32| (R1 + R2 * cos(v)) * cos(u);
-    let fy = |u: f32, v: f32| (R1 + R2 * cos(v)) * sin(u);
-    let fz = |v: f32| R2 * sin(v);
-
-    let mut positions = vec![];
-    let mut colors = vec![];
-    let mut v = 0.0;
+/// Ring torus with major radius `r1` around the z-axis and tube radius `r2`.
+pub struct TorusGeometry {
+    pub r1: f32,
+    pub r2: f32,
+}
 
-    while v <= PI2 {
-        let mut u = 0.0;
+impl Default for TorusGeometry {
+    fn default() -> Self {
+        Self { r1: R1, r2: R2 }
+    }
+}
 
-        while u <= PI2 {
-            let x = fx(u, v);
-            let y = fy(u, v);
-            let z = fz(v);
+impl ParametricSurface for TorusGeometry {
+    fn position(&self, u: f32, v: f32) -> glm::Vec3 {
+        let x = (self.r1 + self.r2 * v.cos()) * u.cos();
+        let y = (self.r1 + self.r2 * v.cos()) * u.sin();
+        let z = self.r2 * v.sin();
+        glm::vec3(x, y, z)
+    }
 
-            positions.push(x);
-            positions.push(y);
-            positions.push(z);
+    fn partials(&self, u: f32, v: f32) -> Option<(glm::Vec3, glm::Vec3)> {
+        let ring = self.r1 + self.r2 * v.cos();
+        let du = glm::vec3(-ring * u.sin(), ring * u.cos(), 0.0);
+        let dv = glm::vec3(-self.r2 * v.sin() * u.cos(), -self.r2 * v.sin() * u.sin(), self.r2 * v.cos());
+        Some((du, dv))
+    }
 
-            colors.push(x + COLOR_STEP);
-            colors.push(y + COLOR_STEP);
-            colors.push(z + COLOR_STEP);
+    fn u_domain(&self) -> (f32, f32) {
+        (0.0, PI2)
+    }
 
-            u += STEP;
-        }
-        v += STEP;
+    fn v_domain(&self) -> (f32, f32) {
+        (0.0, PI2)
     }
 
-    let indices_count = (positions.len() as f32) / 3.0;
+    fn periodic_u(&self) -> bool {
+        true
+    }
 
-    (positions, colors, indices_count as i32)
+    fn periodic_v(&self) -> bool {
+        true
+    }
 }
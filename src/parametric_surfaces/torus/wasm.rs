@@ -0,0 +1,74 @@
+use super::TorusGeometry;
+use crate::error::Error;
+use crate::{camera_controls, frame_captures, lighting_controls, material_controls, mesh_exports, number_js, shader_editing};
+use crate::parametric_surfaces::renderer::SurfaceRenderer;
+use crate::parametric_surfaces::ParametricSurface;
+use js_sys::{JsString, Number};
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+pub struct Torus {
+    geometry: TorusGeometry,
+    renderer: SurfaceRenderer,
+}
+
+#[wasm_bindgen]
+impl Torus {
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas_id: JsString) -> Result<Torus, Error> {
+        let geometry = TorusGeometry::default();
+        let renderer = SurfaceRenderer::try_new(canvas_id, &geometry)?;
+        Ok(Self { geometry, renderer })
+    }
+
+    #[wasm_bindgen]
+    pub fn render(&self, canvas_width: Number, canvas_height: Number, dtheta: Number) -> Result<(), Error> {
+        self.renderer.try_render(canvas_width, canvas_height, dtheta)
+    }
+
+    /// Distance from the centre of the torus to the centre of the tube.
+    #[wasm_bindgen]
+    pub fn set_major_radius(&mut self, radius: Number) -> Result<(), Error> {
+        self.geometry.r1 = number_js!(radius)? as f32;
+        self.renderer.try_set_surface(&self.geometry)
+    }
+
+    /// Radius of the tube.
+    #[wasm_bindgen]
+    pub fn set_minor_radius(&mut self, radius: Number) -> Result<(), Error> {
+        self.geometry.r2 = number_js!(radius)? as f32;
+        self.renderer.try_set_surface(&self.geometry)
+    }
+
+    /// Number of quads around the major and minor circles respectively, at most 1024 each.
+    #[wasm_bindgen]
+    pub fn set_subdivisions(&mut self, u_subdivisions: Number, v_subdivisions: Number) -> Result<(), Error> {
+        let nu = number_js!(u_subdivisions)? as usize;
+        let nv = number_js!(v_subdivisions)? as usize;
+        self.renderer.set_subdivisions(nu, nv)?;
+        self.renderer.try_set_surface(&self.geometry)
+    }
+
+    #[wasm_bindgen]
+    pub fn major_radius(&self) -> f32 {
+        self.geometry.r1
+    }
+
+    #[wasm_bindgen]
+    pub fn minor_radius(&self) -> f32 {
+        self.geometry.r2
+    }
+}
+
+impl Torus {
+    fn surface(&self) -> &dyn ParametricSurface {
+        &self.geometry
+    }
+}
+
+camera_controls!(Torus, renderer.camera, renderer.bounds);
+mesh_exports!(Torus, renderer);
+shader_editing!(Torus, renderer);
+lighting_controls!(Torus, renderer.lighting);
+material_controls!(Torus, renderer.material);
+frame_captures!(Torus, renderer.capture);
@@ -0,0 +1,197 @@
+use super::{geometry, triangle_matrices};
+use crate::camera::Camera;
+use crate::error::{number, Error};
+use crate::lighting::{Lighting, Material};
+use crate::parametric_surfaces::mesh::Bounds;
+use crate::renderer::{self, Drawable, Frame};
+use crate::{gl_context, shader, texture, buf_f32};
+use js_sys::{JsString, Number};
+use nalgebra_glm as glm;
+use std::mem;
+use std::rc::Rc;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+use web_sys::WebGlBuffer as Buffer;
+use web_sys::WebGlRenderingContext as GL;
+use web_sys::WebGlTexture as Texture;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct Triforce {
+    camera: Camera,
+    gl: GL,
+    triforce_shader: Rc<shader::Shader>,
+    buffers: TriforceBuffers,
+    texture: Texture,
+    lighting: Lighting,
+    material: Material,
+}
+
+struct TriforceBuffers {
+    vertices: Buffer,
+    tex_coords: Buffer,
+    position_attr: u32,
+    normal_attr: u32,
+    tex_coord_attr: u32,
+}
+
+type TriforceResult<T> = Result<T, Error>;
+
+/// Distance of the camera from the triforce when drawn on its own.
+const CAMERA_DISTANCE: f32 = 4.0;
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+impl Triforce {
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas_id: JsString) -> TriforceResult<Triforce> {
+        Self::try_new(canvas_id)
+    }
+
+    #[wasm_bindgen]
+    pub fn render(&self, canvas_width: Number, canvas_height: Number, dtheta: Number) -> TriforceResult<()> {
+        self.try_render(canvas_width, canvas_height, dtheta)
+    }
+}
+
+impl Triforce {
+    pub fn try_new(canvas_id: JsString) -> TriforceResult<Self> {
+        let gl = gl_context::init_gl_context(canvas_id)?;
+        Self::try_with_context(gl)
+    }
+
+    pub fn try_render(&self, canvas_width: Number, canvas_height: Number, dtheta: Number) -> TriforceResult<()> {
+        let width = number(&canvas_width, "canvas_width")?;
+        let height = number(&canvas_height, "canvas_height")?;
+        let frame = Frame::new(&self.camera, width, height, number(&dtheta, "dtheta")? as f32);
+
+        renderer::clear(&self.gl);
+        self.draw(&frame, &glm::TMat4::identity())?;
+        self.gl.flush();
+
+        Ok(())
+    }
+
+    /// RGBA pixels of the frame at `dtheta`, rendered offscreen at `width` x `height`.
+    pub fn capture(&self, width: u32, height: u32, dtheta: f32) -> TriforceResult<Vec<u8>> {
+        renderer::read_frame(&self.gl, &self.camera, width, height, dtheta, |frame| {
+            self.draw(frame, &glm::TMat4::identity())
+        })
+    }
+
+    /// Builds a triforce drawing into an existing context, e.g. one owned by a
+    /// [`Renderer`](crate::renderer::Renderer).
+    pub fn try_with_context(gl: GL) -> TriforceResult<Self> {
+        let features = shader::Features { normals: true, colors: false, tex_coords: true };
+        let triforce_shader = shader::Shader::uber(&gl, features)?;
+        let buffers = Self::init_vertices(&gl, &triforce_shader)?;
+        let texture = texture::load_texture(&gl, "public/gold_texture.jpg")?;
+        let lighting = super::lighting();
+        let material = Material::default();
+
+        let camera = Camera::new(CAMERA_DISTANCE);
+
+        Ok( Self { camera, gl, triforce_shader, buffers, texture, lighting, material } )
+    }
+
+    fn init_vertices(gl: &GL, triforce_shader: &shader::Shader) -> TriforceResult<TriforceBuffers> {
+        // *======== Positions and normals data ========*
+        let vertices = gl.create_buffer().ok_or(Error::Allocation("vertices vbo"))?;
+
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vertices));
+
+        let vertices_data = buf_f32!(&geometry::VERTICES);
+
+        gl.buffer_data_with_opt_array_buffer(
+            GL::ARRAY_BUFFER, Some(&vertices_data), GL::STATIC_DRAW
+        );
+
+        // *======== Textures data ========*
+        let tex_coords = gl.create_buffer().ok_or(Error::Allocation("texture coordinates vbo"))?;
+
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&tex_coords));
+
+        let tex_coords_data = buf_f32!(&geometry::TEX_COORDS);
+
+        gl.buffer_data_with_opt_array_buffer(
+            GL::ARRAY_BUFFER, Some(&tex_coords_data), GL::STATIC_DRAW
+        );
+
+        Ok(TriforceBuffers {
+            vertices,
+            tex_coords,
+            position_attr: triforce_shader.attribute_location("position", GL::FLOAT_VEC3)?,
+            normal_attr: triforce_shader.attribute_location("normal", GL::FLOAT_VEC3)?,
+            tex_coord_attr: triforce_shader.attribute_location("texCoord", GL::FLOAT_VEC2)?,
+        })
+    }
+
+    fn bind_attributes(&self) {
+        let gl = &self.gl;
+        let buffers = &self.buffers;
+
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&buffers.vertices));
+        let stride = (6 * mem::size_of::<f32>()) as i32;
+        let offset = (3 * mem::size_of::<f32>()) as i32;
+        gl.vertex_attrib_pointer_with_i32(buffers.position_attr, 3, GL::FLOAT, false, stride, 0);
+        gl.enable_vertex_attrib_array(buffers.position_attr);
+        gl.vertex_attrib_pointer_with_i32(buffers.normal_attr, 3, GL::FLOAT, false, stride, offset);
+        gl.enable_vertex_attrib_array(buffers.normal_attr);
+
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&buffers.tex_coords));
+        gl.vertex_attrib_pointer_with_i32(buffers.tex_coord_attr, 2, GL::FLOAT, false, 0, 0);
+        gl.enable_vertex_attrib_array(buffers.tex_coord_attr);
+    }
+
+    fn unbind_attributes(&self) {
+        self.gl.disable_vertex_attrib_array(self.buffers.position_attr);
+        self.gl.disable_vertex_attrib_array(self.buffers.normal_attr);
+        self.gl.disable_vertex_attrib_array(self.buffers.tex_coord_attr);
+    }
+}
+
+impl Drawable for Triforce {
+    fn draw(&self, frame: &Frame, model: &glm::TMat4<f32>) -> TriforceResult<()> {
+        self.triforce_shader.use_shader(&self.gl);
+        let lighting = frame.lighting.as_ref().unwrap_or(&self.lighting);
+        self.triforce_shader.set_uniform(&self.gl, "ambientLight", &lighting.ambient)?;
+        self.triforce_shader.set_uniform(&self.gl, "lightCount", &(lighting.lights().len() as i32))?;
+        self.triforce_shader.set_uniform(&self.gl, "lights", &lighting.in_view(&frame.view)[..])?;
+        self.triforce_shader.set_uniform(&self.gl, "material", &self.material)?;
+        self.triforce_shader.set_uniform(&self.gl, "baseColor", &glm::vec3(1.0, 1.0, 1.0))?;
+        self.triforce_shader.set_uniform(&self.gl, "uSampler", &0)?;
+        self.triforce_shader.set_uniform(&self.gl, "v", &frame.view)?;
+        self.triforce_shader.set_uniform(&self.gl, "p", &frame.projection)?;
+
+        self.bind_attributes();
+        self.gl.active_texture(GL::TEXTURE0);
+        self.gl.bind_texture(GL::TEXTURE_2D, Some(&self.texture));
+
+        // Top, bottom left and bottom right triangles
+        for local in triangle_matrices(frame.dtheta) {
+            let m = model * local;
+            let n = glm::inverse_transpose(frame.view * m); // normal matrix
+            self.triforce_shader.set_uniform(&self.gl, "m", &m)?;
+            self.triforce_shader.set_uniform(&self.gl, "n", &n)?;
+            self.gl.draw_arrays(GL::TRIANGLES, 0, 3);
+        }
+
+        self.unbind_attributes();
+
+        Ok(())
+    }
+
+    /// The bottom triangles circle the y-axis half a unit out, so every triangle stays within a
+    /// unit of the centre.
+    fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds::cube(1.0))
+    }
+}
+
+#[cfg(feature = "wasm")]
+crate::camera_controls!(Triforce, camera, bounds);
+#[cfg(feature = "wasm")]
+crate::frame_captures!(Triforce, capture);
+#[cfg(feature = "wasm")]
+crate::lighting_controls!(Triforce, lighting);
+#[cfg(feature = "wasm")]
+crate::material_controls!(Triforce, material);
@@ -0,0 +1,120 @@
+//! CPU rendering, for drawing surfaces where there is no browser or GPU (tests, CI, the command
+//! line). Objects describe themselves as [`DrawCall`]s to a [`Backend`], which mirrors the subset
+//! of WebGL the WebGL drawables use and lights them with the same [`Lighting`] and [`Material`].
+
+mod models;
+mod software;
+mod texture;
+
+pub use models::{CubeModel, SurfaceModel, TriforceModel};
+pub use software::SoftwareBackend;
+pub use texture::Texture;
+
+use crate::camera::Camera;
+use crate::lighting::{Lighting, Material};
+use crate::renderer::Frame;
+use nalgebra_glm as glm;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Primitive {
+    Points,
+    Lines,
+    Triangles,
+}
+
+/// One batch of primitives sharing the same state, like a `drawArrays`/`drawElements` call.
+/// Vertex attributes are flat arrays, as in the GPU buffers.
+pub struct DrawCall<'a> {
+    pub primitive: Primitive,
+    pub positions: &'a [f32],
+    /// Needed for lighting; without them vertices are unlit.
+    pub normals: Option<&'a [f32]>,
+    /// Per-vertex RGB. Without them every vertex gets `color`.
+    pub colors: Option<&'a [f32]>,
+    pub color: [f32; 3],
+    pub tex_coords: Option<&'a [f32]>,
+    /// Multiplied with the vertex colour, sampled at `tex_coords`.
+    pub texture: Option<&'a Texture>,
+    /// Blinn-Phong lighting shaded per pixel like the WebGL shaders, with the lights in view space
+    /// as from [`Lighting::to_view`].
+    pub lighting: Option<Lighting>,
+    pub material: Material,
+    /// Vertices in order when `None`.
+    pub indices: Option<Vec<u32>>,
+    pub model_view: glm::TMat4<f32>,
+    pub projection: glm::TMat4<f32>,
+}
+
+impl<'a> DrawCall<'a> {
+    /// Unlit, untextured draw of `positions` in a single colour.
+    pub fn new(primitive: Primitive, positions: &'a [f32], frame: &Frame, model: &glm::TMat4<f32>) -> Self {
+        Self {
+            primitive,
+            positions,
+            normals: None,
+            colors: None,
+            color: [1.0, 1.0, 1.0],
+            tex_coords: None,
+            texture: None,
+            lighting: None,
+            material: Material::default(),
+            indices: None,
+            model_view: frame.view * model,
+            projection: frame.projection,
+        }
+    }
+}
+
+/// Something that can rasterise [`DrawCall`]s.
+pub trait Backend {
+    fn clear(&mut self, color: [f32; 4]);
+    fn draw(&mut self, call: &DrawCall);
+}
+
+/// CPU-side counterpart of `Drawable`.
+pub trait Renderable {
+    /// Issues the object's draw calls without clearing. `model` places the object in the scene;
+    /// any animation of its own is applied on top of it.
+    fn render(&self, backend: &mut dyn Backend, frame: &Frame, model: &glm::TMat4<f32>);
+}
+
+/// Renders `object` alone at `width` x `height`, returning RGBA pixels top row first like the
+/// WebGL `capture` methods.
+pub fn render_frame(object: &dyn Renderable, camera: &Camera, width: u32, height: u32, dtheta: f32) -> Vec<u8> {
+    let mut backend = SoftwareBackend::new(width as usize, height as usize);
+    let frame = Frame::new(camera, width as f64, height as f64, dtheta);
+    backend.clear([0.0, 0.0, 0.0, 1.0]);
+    object.render(&mut backend, &frame, &glm::TMat4::identity());
+    backend.into_pixels()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parametric_surfaces::catalog::Sphere;
+    use crate::parametric_surfaces::mesh::Bounds;
+
+    fn render(model: &SurfaceModel) -> Vec<u8> {
+        let mut camera = Camera::default();
+        camera.fit(&Bounds::cube(1.0), 1.0);
+        render_frame(model, &camera, 32, 32, 0.0)
+    }
+
+    fn pixel(pixels: &[u8], x: usize, y: usize) -> &[u8] {
+        &pixels[(y * 32 + x) * 4..(y * 32 + x) * 4 + 4]
+    }
+
+    #[test]
+    fn surfaces_are_shaded_with_their_lighting_and_material() {
+        let mut model = SurfaceModel::new(&Sphere::default(), 24, 24);
+        let pixels = render(&model);
+        assert_eq!(pixel(&pixels, 0, 0), [0, 0, 0, 255]);
+        assert_ne!(pixel(&pixels, 16, 16), [0, 0, 0, 255]);
+
+        model.material.specular = glm::vec3(0.0, 0.0, 0.0);
+        assert_ne!(render(&model), pixels);
+
+        model.lighting = Lighting::new(glm::vec3(0.0, 0.0, 0.0));
+        assert!(render(&model).chunks_exact(4).all(|pixel| pixel == [0, 0, 0, 255]));
+    }
+}
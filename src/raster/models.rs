@@ -0,0 +1,98 @@
+use super::{Backend, DrawCall, Primitive, Renderable, Texture};
+use crate::lighting::{Lighting, Material};
+use crate::parametric_surfaces::mesh::{self, Mesh};
+use crate::parametric_surfaces::{cube, triforce, ParametricSurface, Spin};
+use crate::renderer::Frame;
+use nalgebra_glm as glm;
+
+/// Colour of the stand-in for the triforce's gold texture, which is only available in the browser.
+const GOLD: [f32; 4] = [0.83, 0.69, 0.22, 1.0];
+
+/// A tessellated surface, drawn the way `SurfaceRenderer` draws it.
+pub struct SurfaceModel {
+    pub mesh: Mesh,
+    pub spin: Spin,
+    /// Used unless the frame brings scene lights.
+    pub lighting: Lighting,
+    pub material: Material,
+}
+
+impl SurfaceModel {
+    pub fn new(surface: &dyn ParametricSurface, u_subdivisions: usize, v_subdivisions: usize) -> Self {
+        Self::from_mesh(mesh::tessellate(surface, u_subdivisions, v_subdivisions))
+    }
+
+    pub fn from_mesh(mesh: Mesh) -> Self {
+        Self { mesh, spin: Spin::default(), lighting: Lighting::default(), material: Material::default() }
+    }
+}
+
+impl Renderable for SurfaceModel {
+    fn render(&self, backend: &mut dyn Backend, frame: &Frame, model: &glm::TMat4<f32>) {
+        let model = model * self.spin.matrix(frame.dtheta);
+        let mut call = DrawCall::new(Primitive::Triangles, &self.mesh.positions, frame, &model);
+        call.normals = Some(&self.mesh.normals);
+        call.colors = Some(&self.mesh.colors);
+        call.lighting = Some(frame.lighting.as_ref().unwrap_or(&self.lighting).to_view(&frame.view));
+        call.material = self.material;
+        call.indices = Some(self.mesh.indices.iter().collect());
+
+        backend.draw(&call);
+    }
+}
+
+/// The three nested wireframe cubes of `Cube`.
+#[derive(Default)]
+pub struct CubeModel;
+
+impl Renderable for CubeModel {
+    fn render(&self, backend: &mut dyn Backend, frame: &Frame, model: &glm::TMat4<f32>) {
+        for (color, local) in cube::nested_cubes(frame.dtheta) {
+            let mut call = DrawCall::new(Primitive::Lines, &cube::geometry::VERTICES, frame, &(model * local));
+            call.color = color;
+            backend.draw(&call);
+        }
+    }
+}
+
+/// The three textured triangles of `Triforce`.
+pub struct TriforceModel {
+    pub texture: Texture,
+    /// Used unless the frame brings scene lights.
+    pub lighting: Lighting,
+    pub material: Material,
+    positions: Vec<f32>,
+    normals: Vec<f32>,
+}
+
+impl Default for TriforceModel {
+    fn default() -> Self {
+        Self::new(Texture::solid(GOLD))
+    }
+}
+
+impl TriforceModel {
+    pub fn new(texture: Texture) -> Self {
+        // Positions and normals are interleaved in the vertex buffer.
+        let vertices = triforce::geometry::VERTICES.chunks_exact(6);
+        let positions = vertices.clone().flat_map(|v| v[..3].to_vec()).collect();
+        let normals = vertices.flat_map(|v| v[3..].to_vec()).collect();
+
+        Self { texture, lighting: triforce::lighting(), material: Material::default(), positions, normals }
+    }
+}
+
+impl Renderable for TriforceModel {
+    fn render(&self, backend: &mut dyn Backend, frame: &Frame, model: &glm::TMat4<f32>) {
+        let lighting = frame.lighting.as_ref().unwrap_or(&self.lighting).to_view(&frame.view);
+        for local in triforce::triangle_matrices(frame.dtheta) {
+            let mut call = DrawCall::new(Primitive::Triangles, &self.positions, frame, &(model * local));
+            call.normals = Some(&self.normals);
+            call.tex_coords = Some(&triforce::geometry::TEX_COORDS);
+            call.texture = Some(&self.texture);
+            call.lighting = Some(lighting.clone());
+            call.material = self.material;
+            backend.draw(&call);
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use super::{Backend, DrawCall, Primitive};
+use nalgebra_glm as glm;
+use std::ops::{Add, Mul};
+
+/// Clip-space planes primitives are clipped against, as `dot(plane, position) >= 0`. Only near
+/// and far need real clipping; anything off to the sides is dropped by the pixel bounds.
+const CLIP_PLANES: [[f32; 4]; 2] = [[0.0, 0.0, 1.0, 1.0], [0.0, 0.0, -1.0, 1.0]];
+
+/// Software rasteriser drawing into an RGBA8 colour buffer with a depth buffer, following WebGL's
+/// defaults: `LESS` depth test, no blending, no face culling.
+pub struct SoftwareBackend {
+    width: usize,
+    height: usize,
+    color: Vec<u8>,
+    depth: Vec<f32>,
+}
+
+/// Values interpolated across primitives, like GLSL varyings. The normal and position are in view
+/// space for lighting.
+#[derive(Clone, Copy, Debug)]
+struct Varyings {
+    color: glm::Vec3,
+    tex_coord: glm::Vec2,
+    normal: glm::Vec3,
+    position: glm::Vec3,
+}
+
+impl Add for Varyings {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            color: self.color + other.color,
+            tex_coord: self.tex_coord + other.tex_coord,
+            normal: self.normal + other.normal,
+            position: self.position + other.position,
+        }
+    }
+}
+
+impl Mul<f32> for Varyings {
+    type Output = Self;
+
+    fn mul(self, k: f32) -> Self {
+        Self { color: self.color * k, tex_coord: self.tex_coord * k, normal: self.normal * k, position: self.position * k }
+    }
+}
+
+/// A vertex after the vertex stage: clip-space position and its varyings.
+#[derive(Clone, Copy, Debug)]
+struct Vertex {
+    position: glm::Vec4,
+    varyings: Varyings,
+}
+
+impl Vertex {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            position: glm::lerp(&self.position, &other.position, t),
+            varyings: self.varyings * (1.0 - t) + other.varyings * t,
+        }
+    }
+}
+
+/// A vertex in window coordinates, with varyings divided by w for perspective-correct
+/// interpolation.
+#[derive(Clone, Copy, Debug)]
+struct ScreenVertex {
+    x: f32,
+    y: f32,
+    depth: f32,
+    inv_w: f32,
+    varyings: Varyings,
+}
+
+impl SoftwareBackend {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut backend = Self {
+            width,
+            height,
+            color: vec![0; width * height * 4],
+            depth: vec![1.0; width * height],
+        };
+        backend.clear([0.0, 0.0, 0.0, 1.0]);
+        backend
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// RGBA8 pixels, top row first.
+    pub fn pixels(&self) -> &[u8] {
+        &self.color
+    }
+
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.color
+    }
+
+    fn shade(&self, call: &DrawCall, normal_matrix: &glm::TMat4<f32>, i: usize) -> Vertex {
+        let attribute3 = |data: &[f32]| glm::make_vec3(&data[i * 3..i * 3 + 3]);
+        let position = attribute3(call.positions);
+        let view_position = call.model_view * glm::vec4(position.x, position.y, position.z, 1.0);
+        let normal = call.normals
+            .map(|normals| (normal_matrix * attribute3(normals).push(0.0)).xyz())
+            .unwrap_or_else(glm::Vec3::zeros);
+
+        let varyings = Varyings {
+            color: call.colors.map(attribute3).unwrap_or_else(|| glm::make_vec3(&call.color)),
+            tex_coord: call.tex_coords
+                .map(|t| glm::vec2(t[i * 2], t[i * 2 + 1]))
+                .unwrap_or_else(|| glm::vec2(0.0, 0.0)),
+            normal,
+            position: view_position.xyz(),
+        };
+
+        Vertex { position: call.projection * view_position, varyings }
+    }
+
+    fn to_screen(&self, vertex: &Vertex) -> ScreenVertex {
+        let inv_w = 1.0 / vertex.position.w;
+        let ndc = vertex.position.xyz() * inv_w;
+
+        ScreenVertex {
+            x: (ndc.x + 1.0) / 2.0 * self.width as f32,
+            y: (1.0 - ndc.y) / 2.0 * self.height as f32,
+            depth: (ndc.z + 1.0) / 2.0,
+            inv_w,
+            varyings: vertex.varyings * inv_w,
+        }
+    }
+
+    /// Depth-tests, shades and writes one fragment, with varyings already interpolated.
+    fn fragment(&mut self, call: &DrawCall, x: usize, y: usize, depth: f32, varyings: &Varyings) {
+        let i = y * self.width + x;
+        if !(0.0..=1.0).contains(&depth) || depth >= self.depth[i] {
+            return;
+        }
+
+        let texel = call.texture
+            .map(|texture| texture.sample(varyings.tex_coord.x, varyings.tex_coord.y))
+            .unwrap_or_else(|| glm::vec4(1.0, 1.0, 1.0, 1.0));
+        let mut color = varyings.color.component_mul(&texel.xyz());
+        if let (Some(lighting), Some(_)) = (&call.lighting, call.normals) {
+            let normal = glm::normalize(&varyings.normal);
+            color = lighting.shade(&call.material, &color, &normal, &varyings.position);
+        }
+        let rgba = [color.x, color.y, color.z, texel.w];
+
+        self.depth[i] = depth;
+        for (channel, value) in self.color[i * 4..i * 4 + 4].iter_mut().zip(rgba) {
+            *channel = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+
+    fn point(&mut self, call: &DrawCall, vertex: &Vertex) {
+        let p = vertex.position;
+        if [p.x, p.y, p.z].iter().any(|c| c.abs() > p.w) {
+            return;
+        }
+
+        let v = self.to_screen(vertex);
+        let (x, y) = (v.x as usize, v.y as usize);
+        if x < self.width && y < self.height {
+            self.fragment(call, x, y, v.depth, &vertex.varyings);
+        }
+    }
+
+    fn line(&mut self, call: &DrawCall, a: &Vertex, b: &Vertex) {
+        let (mut a, mut b) = (*a, *b);
+        for plane in CLIP_PLANES.iter().map(|p| glm::make_vec4(p)) {
+            let (da, db) = (glm::dot(&plane, &a.position), glm::dot(&plane, &b.position));
+            match (da >= 0.0, db >= 0.0) {
+                (false, false) => return,
+                (true, false) => b = a.lerp(&b, da / (da - db)),
+                (false, true) => a = a.lerp(&b, da / (da - db)),
+                (true, true) => (),
+            }
+        }
+
+        let (a, b) = (self.to_screen(&a), self.to_screen(&b));
+        let steps = (b.x - a.x).abs().max((b.y - a.y).abs()).ceil().max(1.0) as usize;
+
+        for k in 0..=steps {
+            let t = k as f32 / steps as f32;
+            let (x, y) = (a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t);
+            if x < 0.0 || y < 0.0 || x >= self.width as f32 || y >= self.height as f32 {
+                continue;
+            }
+
+            let inv_w = a.inv_w + (b.inv_w - a.inv_w) * t;
+            let depth = a.depth + (b.depth - a.depth) * t;
+            let varyings = (a.varyings * (1.0 - t) + b.varyings * t) * (1.0 / inv_w);
+            self.fragment(call, x as usize, y as usize, depth, &varyings);
+        }
+    }
+
+    fn triangle(&mut self, call: &DrawCall, triangle: [Vertex; 3]) {
+        // Sutherland–Hodgman against each clip plane; the result is a convex polygon.
+        let mut polygon = triangle.to_vec();
+        for plane in CLIP_PLANES.iter().map(|p| glm::make_vec4(p)) {
+            let mut clipped = Vec::with_capacity(polygon.len() + 1);
+            for (k, current) in polygon.iter().enumerate() {
+                let next = &polygon[(k + 1) % polygon.len()];
+                let (dc, dn) = (glm::dot(&plane, &current.position), glm::dot(&plane, &next.position));
+                if dc >= 0.0 {
+                    clipped.push(*current);
+                }
+                if (dc >= 0.0) != (dn >= 0.0) {
+                    clipped.push(current.lerp(next, dc / (dc - dn)));
+                }
+            }
+            polygon = clipped;
+        }
+
+        let screen: Vec<ScreenVertex> = polygon.iter().map(|v| self.to_screen(v)).collect();
+        for k in 1..screen.len().saturating_sub(1) {
+            self.fill(call, [screen[0], screen[k], screen[k + 1]]);
+        }
+    }
+
+    /// Fills a screen-space triangle, sampling at pixel centres.
+    fn fill(&mut self, call: &DrawCall, [a, b, c]: [ScreenVertex; 3]) {
+        let edge = |p: &ScreenVertex, q: &ScreenVertex, x: f32, y: f32| (q.x - p.x) * (y - p.y) - (q.y - p.y) * (x - p.x);
+        let area = edge(&a, &b, c.x, c.y);
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+
+        let clamp_x = |x: f32| (x.max(0.0) as usize).min(self.width);
+        let clamp_y = |y: f32| (y.max(0.0) as usize).min(self.height);
+        let (x0, x1) = (clamp_x(a.x.min(b.x).min(c.x).floor()), clamp_x(a.x.max(b.x).max(c.x).ceil()));
+        let (y0, y1) = (clamp_y(a.y.min(b.y).min(c.y).floor()), clamp_y(a.y.max(b.y).max(c.y).ceil()));
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let wa = edge(&b, &c, px, py) / area;
+                let wb = edge(&c, &a, px, py) / area;
+                let wc = edge(&a, &b, px, py) / area;
+                if wa < 0.0 || wb < 0.0 || wc < 0.0 {
+                    continue;
+                }
+
+                let inv_w = wa * a.inv_w + wb * b.inv_w + wc * c.inv_w;
+                let depth = wa * a.depth + wb * b.depth + wc * c.depth;
+                let varyings = (a.varyings * wa + b.varyings * wb + c.varyings * wc) * (1.0 / inv_w);
+                self.fragment(call, x, y, depth, &varyings);
+            }
+        }
+    }
+}
+
+impl Backend for SoftwareBackend {
+    fn clear(&mut self, color: [f32; 4]) {
+        let rgba = color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        for pixel in self.color.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&rgba);
+        }
+        self.depth.fill(1.0);
+    }
+
+    fn draw(&mut self, call: &DrawCall) {
+        let normal_matrix = glm::inverse_transpose(call.model_view);
+        let vertices: Vec<Vertex> = (0..call.positions.len() / 3)
+            .map(|i| self.shade(call, &normal_matrix, i))
+            .collect();
+        let order: Vec<usize> = match &call.indices {
+            Some(indices) => indices.iter().map(|&i| i as usize).collect(),
+            None => (0..vertices.len()).collect(),
+        };
+
+        match call.primitive {
+            Primitive::Points => {
+                for &i in &order {
+                    self.point(call, &vertices[i]);
+                }
+            }
+            Primitive::Lines => {
+                for line in order.chunks_exact(2) {
+                    self.line(call, &vertices[line[0]], &vertices[line[1]]);
+                }
+            }
+            Primitive::Triangles => {
+                for triangle in order.chunks_exact(3) {
+                    self.triangle(call, [vertices[triangle[0]], vertices[triangle[1]], vertices[triangle[2]]]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::Frame;
+
+    const SIZE: usize = 4;
+
+    fn frame() -> Frame {
+        Frame { view: glm::TMat4::identity(), projection: glm::TMat4::identity(), dtheta: 0.0, lighting: None }
+    }
+
+    /// A square over the whole viewport at NDC depth `z`.
+    fn draw_quad(backend: &mut SoftwareBackend, z: f32, color: [f32; 3]) {
+        let positions = [-1.0, -1.0, z, 1.0, -1.0, z, -1.0, 1.0, z, 1.0, 1.0, z];
+        let mut call = DrawCall::new(Primitive::Triangles, &positions, &frame(), &glm::TMat4::identity());
+        call.color = color;
+        call.indices = Some(vec![0, 1, 2, 2, 1, 3]);
+        backend.draw(&call);
+    }
+
+    fn pixel(backend: &SoftwareBackend, x: usize, y: usize) -> &[u8] {
+        let i = (y * backend.width() + x) * 4;
+        &backend.pixels()[i..i + 4]
+    }
+
+    #[test]
+    fn triangles_cover_the_pixel_centres_inside_them() {
+        let mut backend = SoftwareBackend::new(SIZE, SIZE);
+        // The lower left half of the viewport, with its hypotenuse through the diagonal pixel centres.
+        let positions = [-1.0, -1.0, 0.0, 1.0, -1.0, 0.0, -1.0, 1.0, 0.0];
+        backend.draw(&DrawCall::new(Primitive::Triangles, &positions, &frame(), &glm::TMat4::identity()));
+
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let covered = x <= y;
+                let expected = if covered { [255, 255, 255, 255] } else { [0, 0, 0, 255] };
+                assert_eq!(pixel(&backend, x, y), expected, "pixel ({}, {})", x, y);
+                assert_eq!(backend.depth[y * SIZE + x], if covered { 0.5 } else { 1.0 });
+            }
+        }
+    }
+
+    #[test]
+    fn nearer_fragments_win_the_depth_test() {
+        let mut backend = SoftwareBackend::new(SIZE, SIZE);
+        draw_quad(&mut backend, 0.5, [1.0, 0.0, 0.0]);
+        draw_quad(&mut backend, -0.5, [0.0, 1.0, 0.0]);
+        draw_quad(&mut backend, 0.0, [0.0, 0.0, 1.0]);
+
+        assert!(backend.pixels().chunks_exact(4).all(|pixel| pixel == [0, 255, 0, 255]));
+        assert!(backend.depth.iter().all(|&depth| depth == 0.25));
+
+        backend.clear([0.0, 0.0, 0.0, 1.0]);
+        assert!(backend.depth.iter().all(|&depth| depth == 1.0));
+    }
+
+    #[test]
+    fn primitives_beyond_the_clip_planes_are_dropped() {
+        let mut backend = SoftwareBackend::new(SIZE, SIZE);
+        draw_quad(&mut backend, 1.5, [1.0, 1.0, 1.0]);
+        draw_quad(&mut backend, -1.5, [1.0, 1.0, 1.0]);
+
+        assert!(backend.pixels().chunks_exact(4).all(|pixel| pixel == [0, 0, 0, 255]));
+    }
+}
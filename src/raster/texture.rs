@@ -0,0 +1,47 @@
+use nalgebra_glm as glm;
+
+/// RGBA image sampled with bilinear filtering and repeat wrapping, WebGL's defaults for
+/// power-of-two textures. Row 0 is at t = 0, matching an image uploaded without `UNPACK_FLIP_Y`.
+#[derive(Clone, Debug)]
+pub struct Texture {
+    width: usize,
+    height: usize,
+    texels: Vec<glm::Vec4>,
+}
+
+impl Texture {
+    /// Texture from 8-bit RGBA rows, or `None` if `rgba` isn't `width * height * 4` bytes.
+    pub fn from_rgba8(width: usize, height: usize, rgba: &[u8]) -> Option<Self> {
+        if width == 0 || height == 0 || rgba.len() != width * height * 4 {
+            return None;
+        }
+
+        let texels = rgba.chunks_exact(4)
+            .map(|t| glm::vec4(t[0] as f32, t[1] as f32, t[2] as f32, t[3] as f32) / 255.0)
+            .collect();
+        Some(Self { width, height, texels })
+    }
+
+    /// 1×1 texture of a single colour.
+    pub fn solid(color: [f32; 4]) -> Self {
+        Self { width: 1, height: 1, texels: vec![glm::make_vec4(&color)] }
+    }
+
+    pub fn sample(&self, s: f32, t: f32) -> glm::Vec4 {
+        // Texel centres sit at half-integer coordinates.
+        let x = s * self.width as f32 - 0.5;
+        let y = t * self.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+
+        let texel = |x: f32, y: f32| {
+            let x = (x as i64).rem_euclid(self.width as i64) as usize;
+            let y = (y as i64).rem_euclid(self.height as i64) as usize;
+            self.texels[y * self.width + x]
+        };
+
+        let top = glm::lerp(&texel(x0, y0), &texel(x0 + 1.0, y0), fx);
+        let bottom = glm::lerp(&texel(x0, y0 + 1.0), &texel(x0 + 1.0, y0 + 1.0), fx);
+        glm::lerp(&top, &bottom, fy)
+    }
+}
@@ -0,0 +1,72 @@
+//! Per-frame state and scene placement shared by the WebGL and [`raster`](crate::raster) renderers.
+
+#[cfg(feature = "webgl")]
+mod webgl;
+
+#[cfg(feature = "webgl")]
+pub use webgl::{Drawable, Renderer};
+#[cfg(feature = "webgl")]
+pub(crate) use webgl::{clear, read_frame};
+
+use crate::camera::Camera;
+use crate::lighting::Lighting;
+use nalgebra_glm as glm;
+use std::f32::consts::PI;
+
+/// State shared by every object drawn in one frame.
+#[derive(Clone)]
+pub struct Frame {
+    pub view: glm::TMat4<f32>,
+    pub projection: glm::TMat4<f32>,
+    pub dtheta: f32,
+    /// Scene lights used instead of each object's own, as when a `Renderer` draws several objects.
+    pub lighting: Option<Lighting>,
+}
+
+impl Frame {
+    pub fn new(camera: &Camera, canvas_width: f64, canvas_height: f64, dtheta: f32) -> Self {
+        let view = camera.view_matrix();
+        let projection = camera.projection_matrix(canvas_width as f32 / canvas_height as f32);
+        Self { view, projection, dtheta, lighting: None }
+    }
+
+    pub fn with_lighting(self, lighting: Lighting) -> Self {
+        Self { lighting: Some(lighting), ..self }
+    }
+}
+
+/// `dtheta` of each of `frames` frames spread evenly over one revolution, so a turntable loops
+/// without a repeated frame.
+pub fn turntable(frames: u32) -> impl Iterator<Item = f32> {
+    (0..frames).map(move |i| 2.0 * PI * i as f32 / frames as f32)
+}
+
+/// Placement of an object in the scene, applied as translate * rotate * scale.
+pub struct Transform {
+    pub translation: glm::Vec3,
+    /// Euler angles in radians, applied about x, then y, then z.
+    pub rotation: glm::Vec3,
+    pub scale: glm::Vec3,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            translation: glm::vec3(0.0, 0.0, 0.0),
+            rotation: glm::vec3(0.0, 0.0, 0.0),
+            scale: glm::vec3(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl Transform {
+    pub fn matrix(&self) -> glm::TMat4<f32> {
+        let identity = glm::TMat4::identity();
+        let transl = glm::translate(&identity, &self.translation);
+        let rotate = glm::rotate_z(&identity, self.rotation.z)
+            * glm::rotate_y(&identity, self.rotation.y)
+            * glm::rotate_x(&identity, self.rotation.x);
+        let scale = glm::scale(&identity, &self.scale);
+        transl * rotate * scale
+    }
+}
@@ -0,0 +1,166 @@
+#[cfg(feature = "wasm")]
+mod wasm;
+
+use super::{Frame, Transform};
+use crate::camera::Camera;
+use crate::error::{number, Error};
+use crate::gl_context;
+use crate::lighting::Lighting;
+use crate::parametric_surfaces::mesh::Bounds;
+use js_sys::{JsString, Number};
+use nalgebra_glm as glm;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+use web_sys::WebGlRenderingContext as GL;
+
+/// Anything that can be drawn into a shared GL context.
+pub trait Drawable {
+    /// Draws the object without clearing the framebuffer. `model` places the object in the scene;
+    /// any animation of its own is applied on top of it.
+    fn draw(&self, frame: &Frame, model: &glm::TMat4<f32>) -> Result<(), Error>;
+
+    /// Model-space box the object stays within while animating, if known.
+    fn bounds(&self) -> Option<Bounds> {
+        None
+    }
+}
+
+pub(crate) fn clear(gl: &GL) {
+    gl.clear_color(0.0, 0.0, 0.0, 1.0);
+    gl.clear_depth(1.0);
+    gl.clear(GL::COLOR_BUFFER_BIT | GL::DEPTH_BUFFER_BIT);
+}
+
+/// Runs `draw` against an offscreen framebuffer of the given size, independent of the canvas', and
+/// reads the result back as RGBA pixels, top row first.
+pub(crate) fn read_frame<F>(gl: &GL, camera: &Camera, width: u32, height: u32, dtheta: f32, draw: F) -> Result<Vec<u8>, Error>
+    where F: FnOnce(&Frame) -> Result<(), Error>
+{
+    let max_size = gl.get_parameter(GL::MAX_RENDERBUFFER_SIZE)?.as_f64().unwrap_or(0.0) as u32;
+    if width == 0 || height == 0 || width > max_size || height > max_size {
+        return Err(Error::InvalidArgument(format!(
+            "Can't capture a {}x{} frame, sides must be between 1 and {} pixels", width, height, max_size
+        )));
+    }
+    let (w, h) = (width as i32, height as i32);
+
+    // *======== Framebuffer ========*
+    let color = gl.create_texture().ok_or(Error::Allocation("capture texture"))?;
+    gl.bind_texture(GL::TEXTURE_2D, Some(&color));
+    gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+        GL::TEXTURE_2D, 0, GL::RGBA as i32, w, h, 0, GL::RGBA, GL::UNSIGNED_BYTE, None
+    )?;
+    gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::LINEAR as i32);
+    gl.bind_texture(GL::TEXTURE_2D, None);
+
+    let depth = gl.create_renderbuffer().ok_or(Error::Allocation("capture depth buffer"))?;
+    gl.bind_renderbuffer(GL::RENDERBUFFER, Some(&depth));
+    gl.renderbuffer_storage(GL::RENDERBUFFER, GL::DEPTH_COMPONENT16, w, h);
+    gl.bind_renderbuffer(GL::RENDERBUFFER, None);
+
+    let framebuffer = gl.create_framebuffer().ok_or(Error::Allocation("capture framebuffer"))?;
+    gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&framebuffer));
+    gl.framebuffer_texture_2d(GL::FRAMEBUFFER, GL::COLOR_ATTACHMENT0, GL::TEXTURE_2D, Some(&color), 0);
+    gl.framebuffer_renderbuffer(GL::FRAMEBUFFER, GL::DEPTH_ATTACHMENT, GL::RENDERBUFFER, Some(&depth));
+
+    // *======== Draw and read back ========*
+    let mut pixels = vec![0; width as usize * height as usize * 4];
+    let result = if gl.check_framebuffer_status(GL::FRAMEBUFFER) != GL::FRAMEBUFFER_COMPLETE {
+        Err(Error::Allocation("complete capture framebuffer"))
+    } else {
+        gl.viewport(0, 0, w, h);
+        clear(gl);
+        draw(&Frame::new(camera, width as f64, height as f64, dtheta))
+            .and_then(|()| gl.read_pixels_with_opt_u8_array(
+                0, 0, w, h, GL::RGBA, GL::UNSIGNED_BYTE, Some(&mut pixels)
+            ).map_err(Error::from))
+    };
+
+    gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+    gl.viewport(0, 0, gl.drawing_buffer_width(), gl.drawing_buffer_height());
+    gl.delete_framebuffer(Some(&framebuffer));
+    gl.delete_renderbuffer(Some(&depth));
+    gl.delete_texture(Some(&color));
+    result?;
+
+    // GL reads rows bottom up.
+    let stride = width as usize * 4;
+    Ok(pixels.chunks_exact(stride).rev().flatten().copied().collect())
+}
+
+struct SceneObject {
+    id: u32,
+    drawable: Box<dyn Drawable>,
+    transform: Transform,
+}
+
+/// Owns a canvas' GL context and draws any number of objects into it each frame.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub struct Renderer {
+    gl: GL,
+    camera: Camera,
+    /// Lights every object, in place of their own.
+    lighting: Lighting,
+    objects: Vec<SceneObject>,
+    next_id: u32,
+}
+
+type RendererResult<T> = Result<T, Error>;
+
+impl Renderer {
+    pub fn try_new(canvas_id: JsString) -> RendererResult<Self> {
+        gl_context::init_gl_context(canvas_id).map(Self::with_context)
+    }
+
+    /// Builds a renderer drawing into an existing context.
+    pub fn with_context(gl: GL) -> Self {
+        Self { gl, camera: Camera::default(), lighting: Lighting::default(), objects: vec![], next_id: 0 }
+    }
+
+    pub fn try_render(&self, canvas_width: Number, canvas_height: Number, dtheta: Number) -> RendererResult<()> {
+        let width = number(&canvas_width, "canvas_width")?;
+        let height = number(&canvas_height, "canvas_height")?;
+        let frame = Frame::new(&self.camera, width, height, number(&dtheta, "dtheta")? as f32);
+
+        clear(&self.gl);
+        self.draw_objects(&frame)?;
+        self.gl.flush();
+
+        Ok(())
+    }
+
+    /// Adds an already constructed drawable and returns its id.
+    pub fn add(&mut self, drawable: Box<dyn Drawable>) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.objects.push(SceneObject { id, drawable, transform: Transform::default() });
+        id
+    }
+
+    /// Placement of the object with the given id, if there is one.
+    pub fn transform_mut(&mut self, id: u32) -> Option<&mut Transform> {
+        self.objects.iter_mut()
+            .find(|object| object.id == id)
+            .map(|object| &mut object.transform)
+    }
+
+    /// RGBA pixels of the frame at `dtheta`, rendered offscreen at `width` x `height`.
+    pub fn capture(&self, width: u32, height: u32, dtheta: f32) -> RendererResult<Vec<u8>> {
+        read_frame(&self.gl, &self.camera, width, height, dtheta, |frame| self.draw_objects(frame))
+    }
+
+    fn draw_objects(&self, frame: &Frame) -> RendererResult<()> {
+        let frame = frame.clone().with_lighting(self.lighting.clone());
+        for object in &self.objects {
+            object.drawable.draw(&frame, &object.transform.matrix())?;
+        }
+        Ok(())
+    }
+
+    /// Scene-space box around every object with known bounds.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.objects.iter()
+            .filter_map(|object| object.drawable.bounds().map(|b| b.transform(&object.transform.matrix())))
+            .reduce(|a, b| a.union(&b))
+    }
+}
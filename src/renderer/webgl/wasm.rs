@@ -0,0 +1,115 @@
+use super::{Renderer, RendererResult, Transform};
+use crate::error::Error;
+use crate::parametric_surfaces::catalog;
+use crate::parametric_surfaces::cube::Cube;
+use crate::parametric_surfaces::expression::ExpressionGeometry;
+use crate::parametric_surfaces::renderer::SurfaceRenderer;
+use crate::parametric_surfaces::triforce::Triforce;
+use crate::{camera_controls, frame_captures, lighting_controls, number_js, vec3_js};
+use js_sys::{JsString, Number};
+use nalgebra_glm as glm;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+impl Renderer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas_id: JsString) -> RendererResult<Renderer> {
+        Self::try_new(canvas_id)
+    }
+
+    /// Adds a built-in surface by name and returns its id.
+    #[wasm_bindgen]
+    pub fn add_surface(&mut self, name: JsString) -> RendererResult<u32> {
+        let name = String::from(name);
+        let surface = catalog::by_name(&name).ok_or(Error::UnknownSurface(name))?;
+        let drawable = SurfaceRenderer::try_with_context(self.gl.clone(), surface.as_ref())?;
+
+        Ok(self.add(Box::new(drawable)))
+    }
+
+    /// Adds a surface defined by runtime expressions and returns its id.
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_expression(
+        &mut self,
+        x: JsString, y: JsString, z: JsString,
+        u_min: Number, u_max: Number,
+        v_min: Number, v_max: Number,
+    ) -> RendererResult<u32> {
+        let u_domain = (number_js!(u_min)? as f32, number_js!(u_max)? as f32);
+        let v_domain = (number_js!(v_min)? as f32, number_js!(v_max)? as f32);
+        let geometry = ExpressionGeometry::new(
+            &String::from(x), &String::from(y), &String::from(z), u_domain, v_domain
+        )?;
+        let drawable = SurfaceRenderer::try_with_context(self.gl.clone(), &geometry)?;
+
+        Ok(self.add(Box::new(drawable)))
+    }
+
+    #[wasm_bindgen]
+    pub fn add_cube(&mut self) -> RendererResult<u32> {
+        let cube = Cube::try_with_context(self.gl.clone())?;
+        Ok(self.add(Box::new(cube)))
+    }
+
+    #[wasm_bindgen]
+    pub fn add_triforce(&mut self) -> RendererResult<u32> {
+        let triforce = Triforce::try_with_context(self.gl.clone())?;
+        Ok(self.add(Box::new(triforce)))
+    }
+
+    /// Removes an object, returning whether it existed.
+    #[wasm_bindgen]
+    pub fn remove(&mut self, id: u32) -> bool {
+        let count = self.objects.len();
+        self.objects.retain(|object| object.id != id);
+        self.objects.len() != count
+    }
+
+    #[wasm_bindgen]
+    pub fn clear(&mut self) {
+        self.objects.clear();
+    }
+
+    #[wasm_bindgen]
+    pub fn object_count(&self) -> u32 {
+        self.objects.len() as u32
+    }
+
+    #[wasm_bindgen]
+    pub fn set_position(&mut self, id: u32, x: Number, y: Number, z: Number) -> RendererResult<()> {
+        let position = vec3_js!(x, y, z)?;
+        self.transform_or_err(id)?.translation = position;
+        Ok(())
+    }
+
+    /// Sets the rotation as Euler angles in radians, applied about x, then y, then z.
+    #[wasm_bindgen]
+    pub fn set_rotation(&mut self, id: u32, x: Number, y: Number, z: Number) -> RendererResult<()> {
+        let rotation = vec3_js!(x, y, z)?;
+        self.transform_or_err(id)?.rotation = rotation;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn set_scale(&mut self, id: u32, scale: Number) -> RendererResult<()> {
+        let scale = number_js!(scale)? as f32;
+        self.transform_or_err(id)?.scale = glm::vec3(scale, scale, scale);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn render(&self, canvas_width: Number, canvas_height: Number, dtheta: Number) -> RendererResult<()> {
+        self.try_render(canvas_width, canvas_height, dtheta)
+    }
+}
+
+camera_controls!(Renderer, camera, bounds);
+lighting_controls!(Renderer, lighting);
+frame_captures!(Renderer, capture);
+
+impl Renderer {
+    fn transform_or_err(&mut self, id: u32) -> RendererResult<&mut Transform> {
+        self.transform_mut(id).ok_or_else(|| Error::InvalidArgument(format!("No object with id {}", id)))
+    }
+}
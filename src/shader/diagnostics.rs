@@ -0,0 +1,189 @@
+use super::preprocessor::Source;
+use crate::error::ShaderStage;
+use std::fmt;
+
+/// Lines of source shown either side of the offending one.
+const CONTEXT_LINES: usize = 2;
+
+/// One error from a driver's info log, located in the source it was compiled from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub stage: ShaderStage,
+    /// The top-level source or included chunk the error is in, e.g. `uber.vert`.
+    pub file: String,
+    /// 1-based line in `file`.
+    pub line: usize,
+    /// 1-based column, when the driver gives one or the message quotes a token found on the line.
+    pub column: Option<usize>,
+    pub message: String,
+    /// The offending line with its neighbours, numbered, and a marker under the column if known.
+    pub excerpt: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+        write!(f, ": {}", self.message)?;
+        if !self.excerpt.is_empty() {
+            write!(f, "\n{}", self.excerpt)?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors of a compile info log, mapped back through `source`'s includes onto the files they came
+/// from. Lines the log format isn't recognised for are skipped, so the raw log should be kept
+/// alongside.
+pub fn parse(stage: ShaderStage, log: &str, source: &Source) -> Vec<Diagnostic> {
+    log.lines()
+        .filter_map(|entry| parse_entry(entry.trim()))
+        .map(|(line, column, message)| {
+            let (file, line, text) = source.origin(line).unwrap_or(("<preprocessor>", 0, ""));
+            let lines: Vec<&str> = text.lines().collect();
+            let source_line = line.checked_sub(1).and_then(|i| lines.get(i)).copied();
+            // Drivers rarely report columns, but usually quote the token they choke on.
+            let marker = match (column, source_line) {
+                (Some(column), _) => Some((column, 1)),
+                (None, Some(text)) => quoted_token(&message)
+                    .and_then(|token| text.find(token).map(|i| (i + 1, token.chars().count()))),
+                (None, None) => None,
+            };
+
+            Diagnostic {
+                stage,
+                file: file.to_string(),
+                line,
+                column: marker.map(|(column, _)| column),
+                excerpt: excerpt(&lines, line, marker),
+                message,
+            }
+        })
+        .collect()
+}
+
+/// Line, column and message of an error entry, in either of the formats browsers pass through:
+/// `ERROR: 0:12: 'foo' : undeclared identifier` (ANGLE) or `0:12(5): error: ...` (Mesa).
+fn parse_entry(entry: &str) -> Option<(usize, Option<usize>, String)> {
+    if let Some(rest) = entry.strip_prefix("ERROR:") {
+        let mut parts = rest.trim_start().splitn(3, ':');
+        parts.next()?.parse::<u32>().ok()?;
+        let line = parts.next()?.trim().parse().ok()?;
+        let message = parts.next()?.trim().to_string();
+        return Some((line, None, message));
+    }
+
+    let (location, message) = entry.split_once(": ")?;
+    let message = message.strip_prefix("error: ")?;
+    let (source, position) = location.split_once(':')?;
+    source.parse::<u32>().ok()?;
+    let (line, column) = match position.split_once('(') {
+        Some((line, column)) => (line.parse().ok()?, Some(column.strip_suffix(')')?.parse().ok()?)),
+        None => (position.parse().ok()?, None),
+    };
+    Some((line, column, message.to_string()))
+}
+
+/// First `'token'` or `` `token' `` in a message.
+fn quoted_token(message: &str) -> Option<&str> {
+    let start = message.find(['\'', '`'])? + 1;
+    let len = message[start..].find('\'')?;
+    Some(&message[start..start + len]).filter(|token| !token.trim().is_empty())
+}
+
+fn excerpt(lines: &[&str], line: usize, marker: Option<(usize, usize)>) -> String {
+    if line == 0 || line > lines.len() {
+        return String::new();
+    }
+    let first = line.saturating_sub(CONTEXT_LINES).max(1);
+    let last = (line + CONTEXT_LINES).min(lines.len());
+    let width = last.to_string().len();
+
+    let mut out = vec![];
+    for number in first..=last {
+        let pointer = if number == line { '>' } else { ' ' };
+        out.push(format!("{} {:>width$} | {}", pointer, number, lines[number - 1], width = width));
+        if let Some((column, len)) = marker.filter(|_| number == line) {
+            let indent = " ".repeat(column.max(1) - 1);
+            out.push(format!("  {:width$} | {}{}", "", indent, "^".repeat(len.max(1)), width = width));
+        }
+    }
+    out.join("\n").trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shader::preprocessor::preprocess;
+
+    const SHADER: &str = "precision mediump float;\nvoid main() {\n    gl_FragColor = foo;\n}";
+
+    #[test]
+    fn reads_angle_logs() {
+        let source = preprocess("test.frag", SHADER, &[]).unwrap();
+        let log = "ERROR: 0:3: 'foo' : undeclared identifier\nERROR: 1 compilation errors.  No code generated.\n";
+        let diagnostics = parse(ShaderStage::Fragment, log, &source);
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!((diagnostic.file.as_str(), diagnostic.line), ("test.frag", 3));
+        assert_eq!(diagnostic.column, Some(20));
+        assert_eq!(diagnostic.message, "'foo' : undeclared identifier");
+        let excerpt = [
+            "  1 | precision mediump float;",
+            "  2 | void main() {",
+            "> 3 |     gl_FragColor = foo;",
+            "    |                    ^^^",
+            "  4 | }",
+        ];
+        assert_eq!(diagnostic.excerpt, excerpt.join("\n"));
+    }
+
+    #[test]
+    fn reads_mesa_logs() {
+        let source = preprocess("test.frag", SHADER, &[]).unwrap();
+        let log = "0:1(1): warning: extension not supported\n0:3(5): error: `foo' undeclared\n";
+        let diagnostics = parse(ShaderStage::Fragment, log, &source);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 3);
+        assert_eq!(diagnostics[0].column, Some(5));
+        assert_eq!(diagnostics[0].message, "`foo' undeclared");
+        assert!(diagnostics[0].excerpt.ends_with("> 3 |     gl_FragColor = foo;\n    |     ^\n  4 | }"));
+    }
+
+    #[test]
+    fn lines_are_mapped_past_defines_and_into_includes() {
+        let text = "#version 100\n#include \"lighting.glsl\"\nvoid main() {}";
+        let source = preprocess("test.frag", text, &["HAS_NORMALS", "HAS_COLORS"]).unwrap();
+        let last = source.code.lines().count();
+
+        let log = format!("ERROR: 0:3: 'x' : syntax error\nERROR: 0:{}: 'main' : redefinition\nERROR: 0:4: 'y' : oops", last);
+        let diagnostics = parse(ShaderStage::Fragment, &log, &source);
+        let located: Vec<(&str, usize)> = diagnostics.iter().map(|d| (d.file.as_str(), d.line)).collect();
+        assert_eq!(located, [("<preprocessor>", 0), ("test.frag", 3), ("lighting.glsl", 1)]);
+        assert_eq!(diagnostics[0].excerpt, "");
+    }
+
+    #[test]
+    fn displays_location_then_excerpt() {
+        let source = preprocess("test.vert", "void main() {\n    gl_Position = 1;\n}", &[]).unwrap();
+        let diagnostics = parse(ShaderStage::Vertex, "0:2(19): error: type mismatch", &source);
+        assert_eq!(diagnostics[0].to_string(), "\
+test.vert:2:19: type mismatch
+  1 | void main() {
+> 2 |     gl_Position = 1;
+    |                   ^
+  3 | }");
+    }
+
+    #[test]
+    fn quoted_tokens() {
+        assert_eq!(quoted_token("'foo' : undeclared identifier"), Some("foo"));
+        assert_eq!(quoted_token("`foo' undeclared"), Some("foo"));
+        assert_eq!(quoted_token("'' : compilation terminated"), None);
+        assert_eq!(quoted_token("syntax error"), None);
+    }
+}
@@ -0,0 +1,71 @@
+// Per-pixel Blinn-Phong shading by up to MAX_LIGHTS directional, point and spot lights, all in
+// view space. MAX_LIGHTS must match `lighting::MAX_LIGHTS`.
+
+#define MAX_LIGHTS 8
+#define DIRECTIONAL_LIGHT 0
+#define POINT_LIGHT 1
+#define SPOT_LIGHT 2
+
+struct Light {
+    int kind;
+    highp vec3 position;
+    highp vec3 direction; // the way the light shines, for directional and spot lights
+    vec3 color;
+    highp float attenuation; // quadratic falloff with distance, for point and spot lights
+    float innerCone; // cosines of the spot's half angles
+    float outerCone;
+};
+
+struct Material {
+    vec3 diffuse;
+    vec3 specular;
+    float shininess;
+};
+
+uniform Light lights[MAX_LIGHTS];
+uniform int lightCount;
+uniform vec3 ambientLight;
+uniform Material material;
+
+vec3 shade(vec3 baseColor, highp vec3 normal, highp vec3 position)
+{
+    highp vec3 toEye = normalize(-position);
+    vec3 color = ambientLight * baseColor;
+
+    for (int i = 0; i < MAX_LIGHTS; i++) {
+        if (i >= lightCount) {
+            break;
+        }
+        Light light = lights[i];
+
+        highp vec3 direction = light.direction / max(length(light.direction), 1e-4);
+        highp vec3 toLight = -direction;
+        float falloff = 1.0;
+        if (light.kind != DIRECTIONAL_LIGHT) {
+            highp vec3 offset = light.position - position;
+            highp float lightDistance = length(offset);
+            toLight = offset / max(lightDistance, 1e-4);
+            falloff = 1.0 / (1.0 + light.attenuation * lightDistance * lightDistance);
+        }
+        if (light.kind == SPOT_LIGHT) {
+            float cosAngle = dot(-toLight, direction);
+            // smoothstep is undefined unless edge0 < edge1, so a cone without a fade gets a hard edge.
+            if (light.innerCone > light.outerCone) {
+                falloff *= smoothstep(light.outerCone, light.innerCone, cosAngle);
+            } else {
+                falloff *= step(light.outerCone, cosAngle);
+            }
+        }
+
+        float diffuse = max(dot(normal, toLight), 0.0);
+        float specular = 0.0;
+        if (diffuse > 0.0) {
+            highp vec3 halfway = normalize(toLight + toEye);
+            specular = pow(max(dot(normal, halfway), 0.0), material.shininess);
+        }
+
+        color += light.color * falloff * (diffuse * material.diffuse * baseColor + specular * material.specular);
+    }
+
+    return color;
+}
@@ -0,0 +1,25 @@
+precision mediump float;
+varying highp vec3 vColor;
+
+#ifdef HAS_NORMALS
+varying highp vec3 vNormal;
+varying highp vec3 vViewPosition;
+#include "lighting.glsl"
+#endif
+
+#ifdef HAS_TEXCOORD
+varying highp vec2 vTexCoord;
+uniform sampler2D uSampler;
+#endif
+
+void main()
+{
+    vec4 color = vec4(vColor, 1.0);
+#ifdef HAS_TEXCOORD
+    color *= texture2D(uSampler, vTexCoord);
+#endif
+#ifdef HAS_NORMALS
+    color.rgb = shade(color.rgb, normalize(vNormal), vViewPosition);
+#endif
+    gl_FragColor = color;
+}
@@ -0,0 +1,47 @@
+// Shared by every drawable. Optional inputs are switched on with HAS_NORMALS, HAS_COLORS and
+// HAS_TEXCOORD; without colours every vertex gets `baseColor`.
+
+attribute vec3 position;
+uniform mat4 m;
+uniform mat4 v;
+uniform mat4 p;
+varying highp vec3 vColor;
+
+#ifdef HAS_NORMALS
+attribute vec3 normal;
+uniform mat4 n; // normal matrix
+varying highp vec3 vNormal;
+varying highp vec3 vViewPosition;
+#endif
+
+#ifdef HAS_COLORS
+attribute vec3 color;
+#else
+uniform vec3 baseColor;
+#endif
+
+#ifdef HAS_TEXCOORD
+attribute vec2 texCoord;
+varying highp vec2 vTexCoord;
+#endif
+
+void main()
+{
+    highp vec4 viewPosition = v * m * vec4(position, 1.0);
+    gl_Position = p * viewPosition;
+
+#ifdef HAS_COLORS
+    vColor = color;
+#else
+    vColor = baseColor;
+#endif
+
+#ifdef HAS_NORMALS
+    vNormal = (n * vec4(normal, 0.0)).xyz;
+    vViewPosition = viewPosition.xyz;
+#endif
+
+#ifdef HAS_TEXCOORD
+    vTexCoord = texCoord;
+#endif
+}
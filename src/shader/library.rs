@@ -0,0 +1,14 @@
+/// GLSL sources embedded in the crate, by the name `#include` and [`Shader::variant`] use.
+///
+/// [`Shader::variant`]: super::Shader::variant
+const SOURCES: &[(&str, &str)] = &[
+    ("lighting.glsl", include_str!("glsl/lighting.glsl")),
+    ("uber.vert", include_str!("glsl/uber.vert")),
+    ("uber.frag", include_str!("glsl/uber.frag")),
+];
+
+pub fn source(name: &str) -> Option<&'static str> {
+    SOURCES.iter()
+        .find(|(source_name, _)| *source_name == name)
+        .map(|(_, text)| *text)
+}
@@ -0,0 +1,219 @@
+mod diagnostics;
+pub mod library;
+mod preprocessor;
+mod reflection;
+mod uniform;
+
+pub use diagnostics::Diagnostic;
+pub use preprocessor::{preprocess, Source};
+pub use uniform::UniformValue;
+
+use crate::error::{Error, ShaderStage};
+use reflection::{Attribute, Uniform};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+use web_sys::WebGlProgram as Program;
+use web_sys::WebGlRenderingContext as GL;
+use web_sys::{WebGlShader, WebGlUniformLocation};
+
+/// A linked program, with the locations and types of its active uniforms and attributes looked
+/// up once so drawing doesn't query them by name every frame. The program is deleted on drop.
+pub struct Shader {
+    gl: GL,
+    pub program: Program,
+    uniforms: HashMap<String, Uniform>,
+    attributes: HashMap<String, Attribute>,
+}
+
+type ShaderResult<T> = Result<T, Error>;
+
+/// Optional inputs of the uber-shader, each switched on by a `HAS_*` define.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Features {
+    /// Per-vertex `normal`s, lit by the `lighting.glsl` uniforms.
+    pub normals: bool,
+    /// Per-vertex `color`s instead of the `baseColor` uniform.
+    pub colors: bool,
+    /// Per-vertex `texCoord`s sampling `uSampler`.
+    pub tex_coords: bool,
+}
+
+impl Features {
+    pub fn defines(&self) -> Vec<&'static str> {
+        [(self.normals, "HAS_NORMALS"), (self.colors, "HAS_COLORS"), (self.tex_coords, "HAS_TEXCOORD")]
+            .into_iter()
+            .filter_map(|(enabled, define)| enabled.then_some(define))
+            .collect()
+    }
+}
+
+/// A program linked from library sources with a given set of defines. Only weakly held, so the
+/// program (and the context it keeps alive) goes away once the last drawable using it is dropped.
+struct Variant {
+    vertex: &'static str,
+    fragment: &'static str,
+    defines: Vec<&'static str>,
+    shader: Weak<Shader>,
+}
+
+thread_local! {
+    static VARIANTS: RefCell<Vec<Variant>> = const { RefCell::new(vec![]) };
+}
+
+impl Shader {
+    /// The uber-shader with the given optional inputs.
+    pub fn uber(gl: &GL, features: Features) -> ShaderResult<Rc<Self>> {
+        Self::variant(gl, "uber.vert", "uber.frag", &features.defines())
+    }
+
+    /// The library's `vertex` and `fragment` sources compiled with `defines`. Each combination is
+    /// only compiled once per context; later calls share the program while it is still in use.
+    pub fn variant(gl: &GL, vertex: &'static str, fragment: &'static str, defines: &[&'static str]) -> ShaderResult<Rc<Self>> {
+        let mut defines = defines.to_vec();
+        defines.sort_unstable();
+        defines.dedup();
+
+        let cached = VARIANTS.with(|variants| {
+            let mut variants = variants.borrow_mut();
+            variants.retain(|v| v.shader.strong_count() > 0);
+            variants.iter()
+                .filter(|v| v.vertex == vertex && v.fragment == fragment && v.defines == defines)
+                .filter_map(|v| v.shader.upgrade())
+                .find(|shader| shader.gl == *gl)
+        });
+        if let Some(shader) = cached {
+            return Ok(shader);
+        }
+
+        let source = |name: &str| library::source(name)
+            .ok_or_else(|| Error::InvalidArgument(format!("No GLSL source named '{}'", name)))
+            .and_then(|text| preprocess(name, text, &defines));
+        let shader = Rc::new(Self::new(gl, &source(vertex)?, &source(fragment)?)?);
+
+        VARIANTS.with(|variants| variants.borrow_mut().push(Variant {
+            vertex, fragment, defines, shader: Rc::downgrade(&shader)
+        }));
+        Ok(shader)
+    }
+
+    /// Compiles and links preprocessed sources. Compile errors are reported against the files
+    /// the failing lines came from.
+    pub fn new(gl: &GL, vertex: &Source, fragment: &Source) -> ShaderResult<Self> {
+        // *======== Vertex Shader ========*
+        let vertex_shader = compile(gl, ShaderStage::Vertex, vertex)?;
+
+        // *======== Fragment Shader ========*
+        let fragment_shader = match compile(gl, ShaderStage::Fragment, fragment) {
+            Ok(shader) => shader,
+            Err(e) => {
+                gl.delete_shader(Some(&vertex_shader));
+                return Err(e)
+            }
+        };
+
+        // *======== Linking ========*
+        let Some(program) = gl.create_program() else {
+            gl.delete_shader(Some(&vertex_shader));
+            gl.delete_shader(Some(&fragment_shader));
+            return Err(Error::Allocation("shader program"))
+        };
+        gl.attach_shader(&program, &vertex_shader);
+        gl.attach_shader(&program, &fragment_shader);
+        gl.link_program(&program);
+
+        // Shaders successfully linked to our GPU program so shaders on CPU no longer necessary.
+        gl.delete_shader(Some(&vertex_shader));
+        gl.delete_shader(Some(&fragment_shader));
+
+        if !gl.get_program_parameter(&program, GL::LINK_STATUS).as_bool().unwrap_or(false) {
+            let log = gl.get_program_info_log(&program).unwrap_or_default();
+            gl.delete_program(Some(&program));
+            return Err(Error::ShaderLink { log })
+        }
+
+        let uniforms = reflection::uniforms(gl, &program);
+        let attributes = reflection::attributes(gl, &program);
+
+        Ok(Self { gl: gl.clone(), program, uniforms, attributes })
+    }
+
+    pub fn use_shader(&self, gl: &GL) {
+        gl.use_program(Some(&self.program));
+    }
+
+    /// Sets a uniform of the program in use, failing if it isn't active or `value` doesn't match
+    /// its declared type. Struct fields and array elements are named as in GLSL, e.g. `lights[1].color`.
+    pub fn set_uniform<T: UniformValue + ?Sized>(&self, gl: &GL, uniform: &str, value: &T) -> ShaderResult<()> {
+        value.set_uniform(self, gl, uniform)
+    }
+
+    /// Whether the program has an active uniform of that name, or an array or struct uniform with
+    /// elements or fields under it.
+    pub fn has_uniform(&self, uniform: &str) -> bool {
+        self.uniforms.contains_key(uniform) || self.uniforms.keys().any(|name| {
+            name.strip_prefix(uniform).is_some_and(|rest| rest.starts_with(['[', '.']))
+        })
+    }
+
+    /// Whether the program has an active attribute of that name.
+    pub fn has_attribute(&self, attribute: &str) -> bool {
+        self.attributes.contains_key(attribute)
+    }
+
+    /// Location of a vertex attribute, failing if the program doesn't use it or declares it with a
+    /// type other than `kind`.
+    pub fn attribute_location(&self, attribute: &str, kind: u32) -> ShaderResult<u32> {
+        let found = self.attributes.get(attribute)
+            .ok_or_else(|| Error::MissingAttribute(attribute.to_string()))?;
+        check_type(attribute, &[kind], found.kind)?;
+        Ok(found.location)
+    }
+
+    fn uniform_location(&self, uniform: &str, kinds: &[u32]) -> ShaderResult<&WebGlUniformLocation> {
+        let found = self.uniforms.get(uniform)
+            .ok_or_else(|| Error::MissingUniform(uniform.to_string()))?;
+        check_type(uniform, kinds, found.kind)?;
+        Ok(&found.location)
+    }
+
+}
+
+impl Drop for Shader {
+    fn drop(&mut self) {
+        self.gl.delete_program(Some(&self.program));
+    }
+}
+
+/// Fails unless the `declared` type is one of those the value can be `used` as, naming the first
+/// in the error.
+fn check_type(name: &str, used: &[u32], declared: u32) -> ShaderResult<()> {
+    if used.contains(&declared) {
+        return Ok(())
+    }
+    Err(Error::TypeMismatch {
+        name: name.to_string(),
+        declared: reflection::type_name(declared),
+        used: reflection::type_name(used[0]),
+    })
+}
+
+fn compile(gl: &GL, stage: ShaderStage, source: &Source) -> ShaderResult<WebGlShader> {
+    let (kind, what) = match stage {
+        ShaderStage::Vertex => (GL::VERTEX_SHADER, "vertex shader"),
+        ShaderStage::Fragment => (GL::FRAGMENT_SHADER, "fragment shader"),
+    };
+    let shader = gl.create_shader(kind).ok_or(Error::Allocation(what))?;
+
+    gl.shader_source(&shader, &source.code);
+    gl.compile_shader(&shader);
+
+    if !gl.get_shader_parameter(&shader, GL::COMPILE_STATUS).as_bool().unwrap_or(false) {
+        let log = gl.get_shader_info_log(&shader).unwrap_or_default();
+        let diagnostics = diagnostics::parse(stage, &log, source);
+        gl.delete_shader(Some(&shader));
+        return Err(Error::ShaderCompile { stage, log, diagnostics })
+    }
+
+    Ok(shader)
+}
@@ -0,0 +1,131 @@
+use super::library;
+use crate::error::Error;
+
+/// GLSL ready to compile, remembering which file and line each of its lines came from.
+pub struct Source {
+    pub code: String,
+    /// Name and text of the top-level file and everything it included.
+    files: Vec<(String, String)>,
+    /// File index and 1-based line for each line of `code`; `None` for injected `#define`s.
+    origins: Vec<Option<(usize, usize)>>,
+}
+
+impl Source {
+    /// Name of the file the given 1-based line of `code` came from, its line there and the
+    /// file's text.
+    pub fn origin(&self, line: usize) -> Option<(&str, usize, &str)> {
+        let (file, line) = (*self.origins.get(line.checked_sub(1)?)?)?;
+        let (name, text) = &self.files[file];
+        Some((name, line, text))
+    }
+
+    fn push_line(&mut self, line: &str, origin: Option<(usize, usize)>) {
+        self.code.push_str(line);
+        self.code.push('\n');
+        self.origins.push(origin);
+    }
+
+    fn expand<'a>(&mut self, file: usize, lines: impl Iterator<Item = (usize, &'a str)>) -> Result<(), Error> {
+        for (number, line) in lines {
+            let Some(directive) = line.trim_start().strip_prefix("#include") else {
+                self.push_line(line, Some((file, number)));
+                continue;
+            };
+
+            let error = |message: String| Error::Preprocess { file: self.files[file].0.clone(), line: number, message };
+            let name = directive.trim()
+                .strip_prefix('"')
+                .and_then(|rest| rest.strip_suffix('"'))
+                .ok_or_else(|| error(format!("Expected #include \"name\", found '{}'", line.trim())))?;
+            // Like `#pragma once`, so chunks can include what they depend on.
+            if self.files.iter().any(|(included, _)| included == name) {
+                continue;
+            }
+            let text = library::source(name).ok_or_else(|| error(format!("No GLSL chunk named '{}'", name)))?;
+
+            self.files.push((name.to_string(), text.to_string()));
+            self.expand(self.files.len() - 1, numbered(text))?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the `#include "name"` directives of `text` against the embedded library and defines
+/// each of `defines`, after any `#version` line.
+pub fn preprocess(name: &str, text: &str, defines: &[&str]) -> Result<Source, Error> {
+    let mut source = Source { code: String::new(), files: vec![(name.to_string(), text.to_string())], origins: vec![] };
+
+    let header = text.lines()
+        .position(|line| line.trim_start().starts_with("#version"))
+        .map_or(0, |index| index + 1);
+    source.expand(0, numbered(text).take(header))?;
+    for define in defines {
+        source.push_line(&format!("#define {}", define), None);
+    }
+    source.expand(0, numbered(text).skip(header))?;
+
+    Ok(source)
+}
+
+fn numbered(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines().enumerate().map(|(index, line)| (index + 1, line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(text: &str) -> (String, usize, String) {
+        match preprocess("test.frag", text, &[]) {
+            Err(Error::Preprocess { file, line, message }) => (file, line, message),
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("{:?} should not preprocess", text),
+        }
+    }
+
+    #[test]
+    fn defines_go_after_the_version() {
+        let source = preprocess("test.frag", "#version 100\nvoid main() {}", &["HAS_NORMALS"]).unwrap();
+        assert_eq!(source.code, "#version 100\n#define HAS_NORMALS\nvoid main() {}\n");
+        assert_eq!(source.origin(1), Some(("test.frag", 1, "#version 100\nvoid main() {}")));
+        assert_eq!(source.origin(2), None);
+        assert_eq!(source.origin(3).map(|(file, line, _)| (file, line)), Some(("test.frag", 2)));
+
+        let unversioned = preprocess("test.frag", "void main() {}", &["A", "B 2"]).unwrap();
+        assert_eq!(unversioned.code, "#define A\n#define B 2\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn includes_are_expanded_once_in_place() {
+        let text = "#include \"lighting.glsl\"\n  #include \"lighting.glsl\"\nvoid main() {}";
+        let source = preprocess("test.frag", text, &[]).unwrap();
+        let chunk = library::source("lighting.glsl").unwrap();
+
+        assert_eq!(source.code, format!("{}\nvoid main() {{}}\n", chunk.trim_end_matches('\n')));
+        assert_eq!(source.origin(1).map(|(file, line, _)| (file, line)), Some(("lighting.glsl", 1)));
+        let last = source.code.lines().count();
+        assert_eq!(source.origin(last).map(|(file, line, _)| (file, line)), Some(("test.frag", 3)));
+        assert_eq!(source.origin(0), None);
+        assert_eq!(source.origin(last + 1), None);
+    }
+
+    #[test]
+    fn bad_includes_name_their_line() {
+        assert_eq!(
+            failure("void f();\n#include \"missing.glsl\""),
+            ("test.frag".into(), 2, "No GLSL chunk named 'missing.glsl'".into()),
+        );
+        assert_eq!(
+            failure("#include <lighting.glsl>"),
+            ("test.frag".into(), 1, "Expected #include \"name\", found '#include <lighting.glsl>'".into()),
+        );
+    }
+
+    #[test]
+    fn library_shaders_preprocess() {
+        for name in ["uber.vert", "uber.frag"] {
+            let source = preprocess(name, library::source(name).unwrap(), &["HAS_NORMALS"]).unwrap();
+            assert!(!source.code.contains("#include"));
+        }
+    }
+}
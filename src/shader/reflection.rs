@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use web_sys::WebGlProgram as Program;
+use web_sys::WebGlRenderingContext as GL;
+use web_sys::WebGlUniformLocation;
+
+/// An active uniform of a linked program. `kind` is the GL type enum, e.g. `GL::FLOAT_VEC3`.
+pub struct Uniform {
+    pub location: WebGlUniformLocation,
+    pub kind: u32,
+}
+
+pub struct Attribute {
+    pub location: u32,
+    pub kind: u32,
+}
+
+/// Every active uniform by name. Array elements are listed individually as `name[i]`, with the
+/// bare `name` standing for the first element as in GLSL.
+pub fn uniforms(gl: &GL, program: &Program) -> HashMap<String, Uniform> {
+    let mut uniforms = HashMap::new();
+
+    for index in 0..active_count(gl, program, GL::ACTIVE_UNIFORMS) {
+        let Some(info) = gl.get_active_uniform(program, index) else { continue };
+        let kind = info.type_();
+
+        for name in element_names(info.name(), info.size()) {
+            if let Some(location) = gl.get_uniform_location(program, &name) {
+                uniforms.insert(name, Uniform { location, kind });
+            }
+        }
+    }
+
+    uniforms
+}
+
+/// Names an active uniform can be set by: its own, or for an array of `size` reported as `name[0]`,
+/// the bare `name` and every `name[i]`.
+fn element_names(name: String, size: i32) -> Vec<String> {
+    match name.strip_suffix("[0]") {
+        Some(base) => std::iter::once(base.to_string())
+            .chain((0..size).map(|i| format!("{}[{}]", base, i)))
+            .collect(),
+        None => vec![name],
+    }
+}
+
+pub fn attributes(gl: &GL, program: &Program) -> HashMap<String, Attribute> {
+    (0..active_count(gl, program, GL::ACTIVE_ATTRIBUTES))
+        .filter_map(|index| gl.get_active_attrib(program, index))
+        .filter_map(|info| {
+            let location = u32::try_from(gl.get_attrib_location(program, &info.name())).ok()?;
+            Some((info.name(), Attribute { location, kind: info.type_() }))
+        })
+        .collect()
+}
+
+fn active_count(gl: &GL, program: &Program, parameter: u32) -> u32 {
+    gl.get_program_parameter(program, parameter).as_f64().unwrap_or(0.0) as u32
+}
+
+/// GLSL name of a GL type enum, for error messages.
+pub fn type_name(kind: u32) -> &'static str {
+    match kind {
+        GL::FLOAT => "float",
+        GL::FLOAT_VEC2 => "vec2",
+        GL::FLOAT_VEC3 => "vec3",
+        GL::FLOAT_VEC4 => "vec4",
+        GL::INT => "int",
+        GL::INT_VEC2 => "ivec2",
+        GL::INT_VEC3 => "ivec3",
+        GL::INT_VEC4 => "ivec4",
+        GL::BOOL => "bool",
+        GL::BOOL_VEC2 => "bvec2",
+        GL::BOOL_VEC3 => "bvec3",
+        GL::BOOL_VEC4 => "bvec4",
+        GL::FLOAT_MAT2 => "mat2",
+        GL::FLOAT_MAT3 => "mat3",
+        GL::FLOAT_MAT4 => "mat4",
+        GL::SAMPLER_2D => "sampler2D",
+        GL::SAMPLER_CUBE => "samplerCube",
+        _ => "unknown type",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrays_are_listed_by_element() {
+        assert_eq!(element_names("m".to_string(), 1), ["m"]);
+        assert_eq!(element_names("weights[0]".to_string(), 3), ["weights", "weights[0]", "weights[1]", "weights[2]"]);
+        assert_eq!(element_names("single[0]".to_string(), 1), ["single", "single[0]"]);
+    }
+
+    #[test]
+    fn struct_members_keep_their_path() {
+        // Drivers list each member of a struct array on its own, indexed in the middle.
+        assert_eq!(element_names("lights[1].color".to_string(), 1), ["lights[1].color"]);
+        assert_eq!(element_names("lights[0].position".to_string(), 1), ["lights[0].position"]);
+        assert_eq!(element_names("lights[0].cone[0]".to_string(), 2), ["lights[0].cone", "lights[0].cone[0]", "lights[0].cone[1]"]);
+    }
+
+    #[test]
+    fn type_names_match_glsl() {
+        assert_eq!(type_name(GL::FLOAT_VEC3), "vec3");
+        assert_eq!(type_name(GL::FLOAT_MAT4), "mat4");
+        assert_eq!(type_name(GL::SAMPLER_2D), "sampler2D");
+        assert_eq!(type_name(0), "unknown type");
+    }
+}
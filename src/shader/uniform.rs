@@ -0,0 +1,91 @@
+use super::{Shader, ShaderResult};
+use crate::lighting::{Light, LightKind, Material};
+use nalgebra_glm as glm;
+use web_sys::WebGlRenderingContext as GL;
+
+/// A Rust value that can be uploaded to a uniform with [`Shader::set_uniform`].
+///
+/// Slices set one element per index, so a `uniform Light lights[4]` can be set from a
+/// `&[Light]`, with `Light` setting each `{name}.field`.
+pub trait UniformValue {
+    fn set_uniform(&self, shader: &Shader, gl: &GL, name: &str) -> ShaderResult<()>;
+}
+
+/// Implements [`UniformValue`] for a type set by a single GL call, accepting the listed GLSL types.
+macro_rules! uniform_value {
+    ( $t:ty, [$($kind:ident),+], |$gl:ident, $location:ident, $value:ident| $set:expr ) => (
+        impl UniformValue for $t {
+            fn set_uniform(&self, shader: &Shader, gl: &GL, name: &str) -> ShaderResult<()> {
+                let $location = Some(shader.uniform_location(name, &[$(GL::$kind),+])?);
+                let ($gl, $value) = (gl, self);
+                $set;
+                Ok(())
+            }
+        }
+    );
+}
+
+// *======== Scalars ========*
+uniform_value!(f32, [FLOAT], |gl, location, value| gl.uniform1f(location, *value));
+// Samplers take the texture unit they read from.
+uniform_value!(i32, [INT, BOOL, SAMPLER_2D, SAMPLER_CUBE], |gl, location, value| gl.uniform1i(location, *value));
+uniform_value!(bool, [BOOL], |gl, location, value| gl.uniform1i(location, *value as i32));
+
+// *======== Vectors ========*
+uniform_value!(glm::Vec2, [FLOAT_VEC2], |gl, location, value| gl.uniform2fv_with_f32_array(location, value.as_slice()));
+uniform_value!(glm::Vec3, [FLOAT_VEC3], |gl, location, value| gl.uniform3fv_with_f32_array(location, value.as_slice()));
+uniform_value!(glm::Vec4, [FLOAT_VEC4], |gl, location, value| gl.uniform4fv_with_f32_array(location, value.as_slice()));
+uniform_value!(glm::IVec2, [INT_VEC2, BOOL_VEC2], |gl, location, value| gl.uniform2iv_with_i32_array(location, value.as_slice()));
+uniform_value!(glm::IVec3, [INT_VEC3, BOOL_VEC3], |gl, location, value| gl.uniform3iv_with_i32_array(location, value.as_slice()));
+uniform_value!(glm::IVec4, [INT_VEC4, BOOL_VEC4], |gl, location, value| gl.uniform4iv_with_i32_array(location, value.as_slice()));
+
+// *======== Matrices ========*
+// glm stores matrices column-major, as GL expects them.
+uniform_value!(glm::Mat2, [FLOAT_MAT2], |gl, location, value| gl.uniform_matrix2fv_with_f32_array(location, false, value.as_slice()));
+uniform_value!(glm::Mat3, [FLOAT_MAT3], |gl, location, value| gl.uniform_matrix3fv_with_f32_array(location, false, value.as_slice()));
+uniform_value!(glm::Mat4, [FLOAT_MAT4], |gl, location, value| gl.uniform_matrix4fv_with_f32_array(location, false, value.as_slice()));
+
+// *======== Arrays ========*
+impl<T: UniformValue> UniformValue for [T] {
+    fn set_uniform(&self, shader: &Shader, gl: &GL, name: &str) -> ShaderResult<()> {
+        self.iter()
+            .enumerate()
+            .try_for_each(|(i, value)| value.set_uniform(shader, gl, &format!("{}[{}]", name, i)))
+    }
+}
+
+// *======== Lighting ========*
+// Structs of `lighting.glsl`, set field by field. GLSL compilers drop fields a shader never reads,
+// so those are skipped rather than reported missing.
+fn set_field<T: UniformValue>(value: T, shader: &Shader, gl: &GL, name: &str, field: &str) -> ShaderResult<()> {
+    let name = format!("{}.{}", name, field);
+    if shader.has_uniform(&name) {
+        value.set_uniform(shader, gl, &name)?;
+    }
+    Ok(())
+}
+
+impl UniformValue for Light {
+    fn set_uniform(&self, shader: &Shader, gl: &GL, name: &str) -> ShaderResult<()> {
+        let kind: i32 = match self.kind {
+            LightKind::Directional => 0,
+            LightKind::Point => 1,
+            LightKind::Spot => 2,
+        };
+        set_field(kind, shader, gl, name, "kind")?;
+        set_field(self.position, shader, gl, name, "position")?;
+        set_field(self.direction, shader, gl, name, "direction")?;
+        set_field(self.color, shader, gl, name, "color")?;
+        set_field(self.attenuation, shader, gl, name, "attenuation")?;
+        set_field(self.inner_angle.cos(), shader, gl, name, "innerCone")?;
+        set_field(self.outer_angle.cos(), shader, gl, name, "outerCone")
+    }
+}
+
+impl UniformValue for Material {
+    fn set_uniform(&self, shader: &Shader, gl: &GL, name: &str) -> ShaderResult<()> {
+        set_field(self.diffuse, shader, gl, name, "diffuse")?;
+        set_field(self.specular, shader, gl, name, "specular")?;
+        set_field(self.shininess, shader, gl, name, "shininess")
+    }
+}
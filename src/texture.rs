@@ -1,14 +1,14 @@
-use wasm_bindgen::{JsValue, JsCast};
+use crate::error::Error;
+use wasm_bindgen::JsCast;
 use wasm_bindgen::closure::Closure;
 use web_sys::WebGlRenderingContext as GL;
 use web_sys::HtmlImageElement;
 use web_sys::WebGlTexture;
 
-type TextureError<T> = Result<T, JsValue>;
+type TextureResult<T> = Result<T, Error>;
 
-pub fn load_texture(gl: &GL, url: &str) -> TextureError<WebGlTexture> {
-    let texture = gl.create_texture()
-        .ok_or_else(|| JsValue::from_str("Failed to initialize texture."))?;
+pub fn load_texture(gl: &GL, url: &str) -> TextureResult<WebGlTexture> {
+    let texture = gl.create_texture().ok_or(Error::Allocation("texture"))?;
 
     // Load stand-in texture until actual texture finishes downloading.
     let pixel: [u8; 4] = [0, 0, 0, 255];
@@ -19,26 +19,31 @@ pub fn load_texture(gl: &GL, url: &str) -> TextureError<WebGlTexture> {
         GL::RGBA, GL::UNSIGNED_BYTE, Some(&pixel)
     )?;
 
-    let window = web_sys::window()
-        .ok_or_else(|| JsValue::from_str("Failed to get window object."))?;
+    let load_error = |message: &str| Error::TextureLoad { url: url.to_string(), message: message.to_string() };
 
-    let document = window.document()
-        .ok_or_else(|| JsValue::from_str("Failed to get document object."))?;
+    let window = web_sys::window().ok_or_else(|| load_error("Failed to get window object."))?;
+    let document = window.document().ok_or_else(|| load_error("Failed to get document object."))?;
 
-    let img = document.create_element("img")?.dyn_into::<HtmlImageElement>()?;
+    let img = document.create_element("img")?
+        .dyn_into::<HtmlImageElement>()
+        .map_err(|_| load_error("Failed to create image element."))?;
 
     let t = texture.clone();
     let i = img.clone();
     let g = gl.clone();
+    let u = url.to_string();
 
-    // Closure responsible for setting actual texture once img finishes downloading.
+    // Closure responsible for setting actual texture once img finishes downloading. There's no
+    // caller left to return errors to, so they're reported on the console.
     let onload = Closure::wrap(Box::new( move || {
         g.bind_texture(GL::TEXTURE_2D, Some(&t));
         if let Err(e) = g.tex_image_2d_with_u32_and_u32_and_image(
             GL::TEXTURE_2D, 0, GL::RGBA as i32,
             GL::RGBA, GL::UNSIGNED_BYTE, &i
         ) {
-            wasm_bindgen::throw_val(e)
+            let message = e.as_string().unwrap_or_else(|| format!("{:?}", e));
+            web_sys::console::error_1(&Error::TextureLoad { url: u.clone(), message }.into());
+            return;
         }
 
         if is_power_of_2(i.width()) && is_power_of_2(i.height()) {
@@ -50,12 +55,20 @@ pub fn load_texture(gl: &GL, url: &str) -> TextureError<WebGlTexture> {
         }
     }) as Box<dyn Fn()>);
 
+    let u = url.to_string();
+    let onerror = Closure::wrap(Box::new(move || {
+        let message = "The image failed to download or decode.".to_string();
+        web_sys::console::error_1(&Error::TextureLoad { url: u.clone(), message }.into());
+    }) as Box<dyn Fn()>);
+
     img.set_onload(Some(onload.as_ref().unchecked_ref()));
+    img.set_onerror(Some(onerror.as_ref().unchecked_ref()));
 
     // Rust will invalidate the JS closure if onload gets dropped, resulting in a runtime error
     // when the closure gets run. Since this is a global handler, we'll just "forget" onload without
     // invalidating the associated JS callback. This is an intentional memory leak.
     onload.forget();
+    onerror.forget();
 
     img.set_src(url);
 